@@ -1,7 +1,8 @@
+use crate::state::State;
+use crate::tx::Tx;
 use serde::{Deserialize, Serialize};
 use sha2::Digest;
 use wallet::wallet::Wallet;
-use chain::tx::Tx;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Block {
@@ -10,6 +11,10 @@ pub struct Block {
     pub validator: String,
     pub parent_hash: String,
     pub merkle_root: String,
+    #[serde(default)]
+    pub state_root: String,
+    #[serde(default)]
+    pub validators_root: String,
     pub txs: Option<Vec<Tx>>,
     pub signature: String,
 }
@@ -20,15 +25,9 @@ impl Block {
         idx: u64,
         parent_hash: String,
         txs: Vec<Tx>,
+        state: &State,
     ) -> Result<Block, std::io::Error> {
-        let tx_hashes: Vec<[u8; 32]> = txs.clone().iter().map(|tx| tx.hash()).collect();
-        let merkle_tree =
-            rs_merkle::MerkleTree::<rs_merkle::algorithms::Sha256>::from_leaves(&tx_hashes);
-        let merkle_root = if let Some(merkle_root) = merkle_tree.root() {
-            merkle_root
-        } else {
-            [0u8; 32]
-        };
+        let merkle_root = Self::merkle_root(&txs);
         let mut block = Block {
             idx,
             timestamp: std::time::SystemTime::now()
@@ -38,6 +37,8 @@ impl Block {
             validator: wallet.address(),
             parent_hash,
             merkle_root: hex::encode(merkle_root),
+            state_root: hex::encode(state.root()),
+            validators_root: hex::encode(state.validators_root()),
             txs: Some(txs),
             signature: String::from(""),
         };
@@ -46,10 +47,7 @@ impl Block {
     }
 
     pub fn genesis(txs: Vec<Tx>) -> Self {
-        let tx_hashes: Vec<[u8; 32]> = txs.clone().iter().map(|tx| tx.hash()).collect();
-        let merkle_tree =
-            rs_merkle::MerkleTree::<rs_merkle::algorithms::Sha256>::from_leaves(&tx_hashes);
-        let merkle_root = merkle_tree.root().unwrap();
+        let merkle_root = Self::merkle_root(&txs);
         let validator = [0u8; 33];
         let parent_hash = [0u8; 32];
         Block {
@@ -58,11 +56,24 @@ impl Block {
             validator: hex::encode(validator),
             parent_hash: hex::encode(parent_hash),
             merkle_root: hex::encode(merkle_root),
+            state_root: String::new(),
+            validators_root: String::new(),
             txs: Some(txs),
             signature: String::from("GENESIS"),
         }
     }
 
+    pub fn merkle_root(txs: &[Tx]) -> [u8; 32] {
+        let tx_hashes: Vec<[u8; 32]> = txs.iter().map(|tx| tx.hash()).collect();
+        let merkle_tree =
+            rs_merkle::MerkleTree::<rs_merkle::algorithms::Sha256>::from_leaves(&tx_hashes);
+        if let Some(merkle_root) = merkle_tree.root() {
+            merkle_root
+        } else {
+            [0u8; 32]
+        }
+    }
+
     pub fn txs(&self) -> Option<Vec<Tx>> {
         if let Some(txs) = self.txs.clone() {
             Some(txs)
@@ -78,10 +89,41 @@ impl Block {
         hasher.update(self.validator.as_bytes());
         hasher.update(self.parent_hash.as_bytes());
         hasher.update(self.merkle_root.as_bytes());
+        // Genesis and blocks stored before the state roots were added have none.
+        if !self.state_root.is_empty() {
+            hasher.update(self.state_root.as_bytes());
+            hasher.update(self.validators_root.as_bytes());
+        }
         hasher.finalize().into()
     }
 
     pub fn hash_str(&self) -> String {
         hex::encode(self.hash())
     }
+
+    pub fn valid(&self) -> bool {
+        let Ok(key_bytes) = hex::decode(&self.validator) else {
+            return false;
+        };
+        let Ok(key_bytes) = <[u8; 33]>::try_from(key_bytes) else {
+            return false;
+        };
+        let Ok(public_key) = libsecp256k1::PublicKey::parse_compressed(&key_bytes) else {
+            return false;
+        };
+        let Ok(signature) = hex::decode(&self.signature) else {
+            return false;
+        };
+        let Ok(signature) = <[u8; 64]>::try_from(signature) else {
+            return false;
+        };
+        match libsecp256k1::Signature::parse_standard(&signature) {
+            Ok(signature) => libsecp256k1::verify(
+                &libsecp256k1::Message::parse(&self.hash()),
+                &signature,
+                &public_key,
+            ),
+            Err(_) => false,
+        }
+    }
 }
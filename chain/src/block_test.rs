@@ -1,6 +1,7 @@
+use crate::block::Block;
+use crate::state::State;
+use crate::tx::Tx;
 use wallet::wallet::Wallet;
-use crate::blockchain::block::Block;
-use chain::tx::Tx;
 
 #[test]
 fn test_genesis_block_creation() -> Result<(), std::io::Error> {
@@ -28,6 +29,6 @@ fn test_new_block_creation() -> Result<(), std::io::Error> {
     txs.push(tx);
 
     let genesis = Block::genesis(txs.clone());
-    let block = Block::new(&wallet, 1, genesis.hash_str(), txs)?;
+    let block = Block::new(&wallet, 1, genesis.hash_str(), txs, &State::default())?;
     Ok(())
 }
@@ -0,0 +1,130 @@
+use crate::block::Block;
+use crate::stake::{self, Stake};
+use crate::state::State;
+
+pub const DEFAULT_FINALITY_DEPTH: u64 = 10;
+
+// Header chain followed from a trusted block, checking that every header is signed by
+// the validator selected from the stakes committed in its parent.
+pub struct HeaderChain {
+    headers: Vec<Block>,
+    stakes: Vec<Stake>,
+}
+
+impl HeaderChain {
+    pub fn new(trusted: Block, stakes: Vec<Stake>) -> Self {
+        let mut trusted = trusted;
+        trusted.txs = None;
+        Self {
+            headers: vec![trusted],
+            stakes,
+        }
+    }
+
+    // Genesis commits no roots, its validator set is derived from its txs.
+    pub fn genesis(genesis: &Block) -> Result<Self, std::io::Error> {
+        let Some(txs) = genesis.txs() else {
+            return Err(Self::invalid("Genesis block has no txs"));
+        };
+        if genesis.idx != 0 || genesis.merkle_root != hex::encode(Block::merkle_root(&txs)) {
+            return Err(Self::invalid("Invalid genesis block"));
+        }
+        let mut state = State::default();
+        for tx in &txs {
+            state.apply(tx);
+        }
+        Ok(Self::new(genesis.clone(), state.stakes()))
+    }
+
+    fn invalid(message: impl Into<String>) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, message.into())
+    }
+
+    pub fn first(&self) -> &Block {
+        &self.headers[0]
+    }
+
+    pub fn head(&self) -> &Block {
+        self.headers.last().expect("trusted header")
+    }
+
+    pub fn height(&self) -> u64 {
+        self.head().idx
+    }
+
+    pub fn header(&self, idx: u64) -> Option<&Block> {
+        let offset = idx.checked_sub(self.first().idx)?;
+        self.headers.get(offset as usize)
+    }
+
+    pub fn headers(&self) -> &[Block] {
+        &self.headers
+    }
+
+    // Drops the headers below `idx`, the head is always kept.
+    pub fn truncate_below(&mut self, idx: u64) {
+        let count = idx.saturating_sub(self.first().idx) as usize;
+        self.headers.drain(..count.min(self.headers.len() - 1));
+    }
+
+    pub fn stakes(&self) -> &[Stake] {
+        &self.stakes
+    }
+
+    pub fn validators_changed(&self, header: &Block) -> bool {
+        header.validators_root != hex::encode(stake::stakes_root(&self.stakes))
+    }
+
+    // Stakes are only needed when the header commits a different validator set.
+    pub fn append(
+        &mut self,
+        header: &Block,
+        stakes: Option<Vec<Stake>>,
+    ) -> Result<(), std::io::Error> {
+        let head = self.head();
+        if header.idx != head.idx + 1 || header.parent_hash != head.hash_str() {
+            return Err(Self::invalid(format!(
+                "Block {} does not extend the header chain",
+                header.idx
+            )));
+        }
+        if !header.valid() {
+            return Err(Self::invalid(format!(
+                "Invalid signature of block {}",
+                header.idx
+            )));
+        }
+        let selected = stake::select_validator(&head.hash_str(), &self.stakes);
+        if selected.as_deref() != Some(header.validator.as_str()) {
+            return Err(Self::invalid(format!(
+                "Block {} is not signed by the selected validator",
+                header.idx
+            )));
+        }
+        if header.state_root.is_empty() {
+            return Err(Self::invalid(format!(
+                "Block {} has no state root",
+                header.idx
+            )));
+        }
+        if self.validators_changed(header) {
+            let Some(stakes) = stakes else {
+                return Err(Self::invalid(format!(
+                    "Validator set of block {} is required",
+                    header.idx
+                )));
+            };
+            if hex::encode(stake::stakes_root(&stakes)) != header.validators_root {
+                return Err(Self::invalid(format!(
+                    "Validator set does not match block {}",
+                    header.idx
+                )));
+            }
+            self.stakes = stakes;
+        }
+        let mut header = header.clone();
+        header.txs = None;
+        self.headers.push(header);
+        Ok(())
+    }
+}
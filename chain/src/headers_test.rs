@@ -0,0 +1,78 @@
+use crate::block::Block;
+use crate::headers::HeaderChain;
+use crate::state::State;
+use crate::system::STAKE_WALLET;
+use crate::tx::Tx;
+use wallet::wallet::Wallet;
+
+#[test]
+fn test_header_chain() {
+    let validator = Wallet::new();
+    let user = Wallet::new();
+    let genesis_txs = vec![
+        Tx::new(
+            &validator,
+            String::from(STAKE_WALLET),
+            String::from("500"),
+            1,
+        )
+        .unwrap(),
+        Tx::new(&validator, user.address(), String::from("100"), 2).unwrap(),
+    ];
+    let mut state = State::default();
+    for tx in &genesis_txs {
+        state.apply(tx);
+    }
+    let genesis = Block::genesis(genesis_txs);
+    let mut chain = HeaderChain::genesis(&genesis).unwrap();
+
+    let forged = Block::new(&user, 1, genesis.hash_str(), vec![], &state).unwrap();
+    assert!(chain.append(&forged, None).is_err());
+
+    let stake = Tx::new(&user, String::from(STAKE_WALLET), String::from("50"), 1).unwrap();
+    state.apply(&stake);
+    let first = Block::new(&validator, 1, genesis.hash_str(), vec![stake], &state).unwrap();
+    assert!(chain.validators_changed(&first));
+    assert!(chain.append(&first, None).is_err());
+    assert!(chain.append(&first, Some(vec![])).is_err());
+    chain.append(&first, Some(state.stakes())).unwrap();
+    assert_eq!(chain.height(), 1);
+    assert_eq!(chain.stakes().len(), 2);
+    assert_eq!(chain.header(1).unwrap().hash_str(), first.hash_str());
+
+    let validator_at = |parent: &Block| {
+        crate::stake::select_validator(&parent.hash_str(), &state.stakes()).unwrap()
+    };
+    let signer = |address: String| {
+        if address == user.address() {
+            &user
+        } else {
+            &validator
+        }
+    };
+    let second = Block::new(
+        signer(validator_at(&first)),
+        2,
+        first.hash_str(),
+        vec![],
+        &state,
+    )
+    .unwrap();
+    chain.append(&second, None).unwrap();
+    chain.truncate_below(1);
+    assert!(chain.header(0).is_none());
+    assert_eq!(chain.first().hash_str(), first.hash_str());
+    chain.truncate_below(100);
+    assert_eq!(chain.headers().len(), 1);
+    assert_eq!(chain.head().hash_str(), second.hash_str());
+    let third = Block::new(
+        signer(validator_at(&second)),
+        3,
+        second.hash_str(),
+        vec![],
+        &state,
+    )
+    .unwrap();
+    chain.append(&third, None).unwrap();
+    assert_eq!(chain.height(), 3);
+}
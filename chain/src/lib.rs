@@ -1,3 +1,22 @@
+pub mod block;
+#[cfg(test)]
+mod block_test;
+pub mod headers;
+#[cfg(test)]
+mod headers_test;
+pub mod proof;
+#[cfg(test)]
+mod proof_test;
+pub mod receipt;
+pub mod snapshot;
+pub mod stake;
+#[cfg(test)]
+mod stake_test;
+pub mod state;
+#[cfg(test)]
+mod state_test;
+pub mod system;
 pub mod tx;
 #[cfg(test)]
-mod tx_test;
\ No newline at end of file
+mod tx_test;
+pub mod wire;
@@ -0,0 +1,143 @@
+use crate::block::Block;
+use crate::snapshot::AccountState;
+use crate::tx::Tx;
+use rs_merkle::MerkleTree;
+use rs_merkle::algorithms::Sha256;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TxProof {
+    pub tx: Tx,
+    pub header: Block,
+    pub idx: usize,
+    pub total: usize,
+    pub proof: Vec<String>,
+}
+
+impl TxProof {
+    pub fn new(tx: Tx, header: Block, leaves: &[[u8; 32]]) -> Option<Self> {
+        let idx = leaves.iter().position(|leaf| *leaf == tx.hash())?;
+        let tree = rs_merkle::MerkleTree::<rs_merkle::algorithms::Sha256>::from_leaves(leaves);
+        Some(Self {
+            tx,
+            header,
+            idx,
+            total: leaves.len(),
+            proof: tree.proof(&[idx]).proof_hashes_hex(),
+        })
+    }
+
+    pub fn verify(&self) -> bool {
+        self.verify_against(&self.header)
+    }
+
+    pub fn verify_against(&self, header: &Block) -> bool {
+        self.header.hash() == header.hash()
+            && verify_inclusion(
+                &header.merkle_root,
+                self.tx.hash(),
+                self.idx,
+                self.total,
+                &self.proof,
+            )
+    }
+}
+
+// An account proven against the state root of a header.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AccountProof {
+    pub account: AccountState,
+    pub header: Block,
+    pub idx: usize,
+    pub total: usize,
+    pub proof: Vec<String>,
+}
+
+impl AccountProof {
+    // `accounts` is the whole state in the order its root is built from.
+    pub fn new(address: &str, header: Block, accounts: &[AccountState]) -> Option<Self> {
+        AccountTree::new(accounts.to_vec()).proof(address, header)
+    }
+
+    pub fn verify(&self) -> bool {
+        self.verify_against(&self.header)
+    }
+
+    pub fn verify_against(&self, header: &Block) -> bool {
+        self.header.hash() == header.hash()
+            && verify_inclusion(
+                &header.state_root,
+                self.account.hash(),
+                self.idx,
+                self.total,
+                &self.proof,
+            )
+    }
+}
+
+// Merkle tree over the whole state, built once and reused for the proofs of every account.
+pub struct AccountTree {
+    accounts: Vec<AccountState>,
+    tree: MerkleTree<Sha256>,
+}
+
+impl AccountTree {
+    // `accounts` is the whole state in the order its root is built from.
+    pub fn new(accounts: Vec<AccountState>) -> Self {
+        let leaves: Vec<[u8; 32]> = accounts.iter().map(|account| account.hash()).collect();
+        Self {
+            accounts,
+            tree: MerkleTree::<Sha256>::from_leaves(&leaves),
+        }
+    }
+
+    pub fn root(&self) -> String {
+        hex::encode(self.tree.root().unwrap_or_default())
+    }
+
+    pub fn proof(&self, address: &str, header: Block) -> Option<AccountProof> {
+        let idx = self
+            .accounts
+            .iter()
+            .position(|account| account.address == address)?;
+        Some(AccountProof {
+            account: self.accounts[idx].clone(),
+            header,
+            idx,
+            total: self.accounts.len(),
+            proof: self.tree.proof(&[idx]).proof_hashes_hex(),
+        })
+    }
+}
+
+pub fn verify_inclusion(
+    merkle_root: &str,
+    leaf: [u8; 32],
+    idx: usize,
+    total: usize,
+    proof: &[String],
+) -> bool {
+    let Some(root) = decode_hash(merkle_root) else {
+        return false;
+    };
+    let mut hashes = Vec::new();
+    for hash in proof {
+        let Some(hash) = decode_hash(hash) else {
+            return false;
+        };
+        hashes.push(hash);
+    }
+    idx < total
+        && rs_merkle::MerkleProof::<rs_merkle::algorithms::Sha256>::new(hashes).verify(
+            root,
+            &[idx],
+            &[leaf],
+            total,
+        )
+}
+
+fn decode_hash(hash: &str) -> Option<[u8; 32]> {
+    hex::decode(hash)
+        .ok()
+        .and_then(|hash| <[u8; 32]>::try_from(hash).ok())
+}
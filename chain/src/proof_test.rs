@@ -0,0 +1,105 @@
+use crate::block::Block;
+use crate::proof::{AccountProof, TxProof, verify_inclusion};
+use crate::state::State;
+use crate::tx::Tx;
+use wallet::wallet::Wallet;
+
+fn block_with_txs(wallet: &Wallet, count: u64) -> Result<Block, std::io::Error> {
+    let mut txs = Vec::new();
+    for nonce in 1..=count {
+        txs.push(Tx::new(wallet, wallet.address(), String::from("1"), nonce)?);
+    }
+    Block::new(wallet, 1, String::from("parent"), txs, &State::default())
+}
+
+fn proof(block: &Block, idx: usize) -> TxProof {
+    let txs = block.txs().unwrap();
+    let leaves: Vec<[u8; 32]> = txs.iter().map(|tx| tx.hash()).collect();
+    let mut header = block.clone();
+    header.txs = None;
+    TxProof::new(txs[idx].clone(), header, &leaves).unwrap()
+}
+
+#[test]
+fn test_tx_proof_verify() -> Result<(), std::io::Error> {
+    let wallet = Wallet::new();
+    let block = block_with_txs(&wallet, 5)?;
+    for idx in 0..5 {
+        let proof = proof(&block, idx);
+        assert!(proof.verify());
+        assert!(proof.verify_against(&block));
+        assert!(verify_inclusion(
+            &block.merkle_root,
+            proof.tx.hash(),
+            proof.idx,
+            proof.total,
+            &proof.proof
+        ));
+    }
+
+    let single = block_with_txs(&wallet, 1)?;
+    assert!(proof(&single, 0).verify_against(&single));
+    Ok(())
+}
+
+#[test]
+fn test_tx_proof_rejects_tampering() -> Result<(), std::io::Error> {
+    let wallet = Wallet::new();
+    let block = block_with_txs(&wallet, 4)?;
+    let other = block_with_txs(&wallet, 3)?;
+    let proof = proof(&block, 1);
+    assert!(!proof.verify_against(&other));
+
+    let mut tampered = proof.clone();
+    tampered.idx = 2;
+    assert!(!tampered.verify());
+    let mut tampered = proof.clone();
+    tampered.total = 1;
+    assert!(!tampered.verify());
+    let mut tampered = proof.clone();
+    tampered.proof[0] = String::from("00");
+    assert!(!tampered.verify());
+    let mut tampered = proof.clone();
+    tampered.header.merkle_root = other.merkle_root.clone();
+    assert!(!tampered.verify_against(&block));
+    assert!(!verify_inclusion(
+        &other.merkle_root,
+        proof.tx.hash(),
+        proof.idx,
+        proof.total,
+        &proof.proof
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_account_proof_verify() -> Result<(), std::io::Error> {
+    let wallet = Wallet::new();
+    let mut state = State::default();
+    for nonce in 1..=3 {
+        let to = format!("wallet{}", nonce);
+        state.apply(&Tx::new(&wallet, to, String::from("1"), nonce)?);
+    }
+    let block = Block::new(&wallet, 1, String::from("parent"), Vec::new(), &state)?;
+    let accounts = state.accounts();
+    for account in &accounts {
+        let proof = AccountProof::new(&account.address, block.clone(), &accounts).unwrap();
+        assert_eq!(proof.account, *account);
+        assert!(proof.verify_against(&block));
+    }
+    assert!(AccountProof::new("missing", block.clone(), &accounts).is_none());
+
+    let mut tampered = AccountProof::new(&wallet.address(), block.clone(), &accounts).unwrap();
+    tampered.account.balance = String::from("1000");
+    assert!(!tampered.verify_against(&block));
+    let other = Block::new(
+        &wallet,
+        1,
+        String::from("parent"),
+        Vec::new(),
+        &State::default(),
+    )?;
+    let proof = AccountProof::new(&wallet.address(), block, &accounts).unwrap();
+    assert!(!proof.verify_against(&other));
+    Ok(())
+}
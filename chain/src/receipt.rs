@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TxStatus {
+    Pending,
+    Included,
+    Finalized,
+    Dropped,
+    Replaced,
+}
+
+// The chain charges no transaction fees, so receipts have no fee field. `success` is set once
+// the tx is included in a block, a dropped or replaced tx records why in `reason`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Receipt {
+    pub hash: String,
+    pub status: TxStatus,
+    pub block: Option<u64>,
+    pub index: Option<u64>,
+    #[serde(default)]
+    pub success: bool,
+    pub reason: Option<String>,
+    pub replaced_by: Option<String>,
+}
+
+impl Receipt {
+    pub fn pending(hash: String) -> Self {
+        Self {
+            hash,
+            status: TxStatus::Pending,
+            block: None,
+            index: None,
+            success: false,
+            reason: None,
+            replaced_by: None,
+        }
+    }
+
+    pub fn included(hash: String, block: u64, index: u64) -> Self {
+        Self {
+            status: TxStatus::Included,
+            block: Some(block),
+            index: Some(index),
+            success: true,
+            ..Self::pending(hash)
+        }
+    }
+
+    pub fn dropped(hash: String, reason: String) -> Self {
+        Self {
+            status: TxStatus::Dropped,
+            reason: Some(reason),
+            ..Self::pending(hash)
+        }
+    }
+
+    pub fn replaced(hash: String, by: String) -> Self {
+        Self {
+            status: TxStatus::Replaced,
+            reason: Some(format!("Replaced by {}", by)),
+            replaced_by: Some(by),
+            ..Self::pending(hash)
+        }
+    }
+
+    pub fn finalize(mut self, finalized_height: u64) -> Self {
+        if self.status == TxStatus::Included
+            && self.block.is_some_and(|idx| idx <= finalized_height)
+        {
+            self.status = TxStatus::Finalized;
+        }
+        self
+    }
+}
@@ -0,0 +1,105 @@
+use crate::block::Block;
+use crate::state::State;
+use serde::{Deserialize, Serialize};
+use sha2::Digest;
+
+pub const CHUNK_SIZE: usize = 256;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AccountState {
+    pub address: String,
+    pub balance: String,
+    pub stake: String,
+    pub nonce: u64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StateChunk {
+    pub idx: u64,
+    pub accounts: Vec<AccountState>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotManifest {
+    pub height: u64,
+    pub block: Block,
+    pub state_root: String,
+    pub chunks: Vec<String>,
+    // Txs included up to and including the snapshot block, informational only.
+    #[serde(default)]
+    pub tx_count: u64,
+}
+
+impl AccountState {
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(self.address.as_bytes());
+        hasher.update(self.balance.as_bytes());
+        hasher.update(self.stake.as_bytes());
+        hasher.update(self.nonce.to_be_bytes());
+        hasher.finalize().into()
+    }
+}
+
+impl StateChunk {
+    pub fn split(accounts: Vec<AccountState>) -> Vec<StateChunk> {
+        accounts
+            .chunks(CHUNK_SIZE)
+            .enumerate()
+            .map(|(idx, accounts)| StateChunk {
+                idx: idx as u64,
+                accounts: accounts.to_vec(),
+            })
+            .collect()
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(self.idx.to_be_bytes());
+        for account in &self.accounts {
+            hasher.update(account.hash());
+        }
+        hasher.finalize().into()
+    }
+
+    pub fn hash_str(&self) -> String {
+        hex::encode(self.hash())
+    }
+}
+
+impl SnapshotManifest {
+    pub fn new(block: Block, chunks: &[StateChunk], tx_count: u64) -> Self {
+        let mut block = block;
+        block.txs = None;
+        Self {
+            height: block.idx,
+            state_root: block.state_root.clone(),
+            block,
+            chunks: chunks.iter().map(|chunk| chunk.hash_str()).collect(),
+            tx_count,
+        }
+    }
+
+    pub fn valid(&self) -> bool {
+        self.block.idx == self.height
+            && self.block.valid()
+            && !self.state_root.is_empty()
+            && self.state_root == self.block.state_root
+    }
+
+    pub fn verify_chunk(&self, chunk: &StateChunk) -> bool {
+        self.chunks.get(chunk.idx as usize) == Some(&chunk.hash_str())
+    }
+
+    // The accounts of all chunks must add up to the state root of the block header.
+    pub fn verify_state(&self, chunks: &[StateChunk]) -> bool {
+        let accounts = chunks
+            .iter()
+            .flat_map(|chunk| chunk.accounts.clone())
+            .collect();
+        match State::new(accounts) {
+            Ok(state) => hex::encode(state.root()) == self.state_root,
+            Err(_) => false,
+        }
+    }
+}
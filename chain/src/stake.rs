@@ -0,0 +1,83 @@
+use crate::system::MINIMUM_STAKE;
+use bigdecimal::Zero;
+use bigdecimal::num_bigint::BigInt;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::Digest;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stake {
+    wallet: String,
+    #[serde(serialize_with = "to_string", deserialize_with = "from_string")]
+    stake: BigInt,
+}
+
+fn to_string<S: Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&value.to_string())
+}
+
+fn from_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    BigInt::from_str(&value).map_err(serde::de::Error::custom)
+}
+
+impl Stake {
+    pub fn new(wallet: String, stake: BigInt) -> Option<Self> {
+        if stake < BigInt::from(MINIMUM_STAKE) {
+            return None;
+        }
+        Some(Self { wallet, stake })
+    }
+
+    pub fn wallet(&self) -> String {
+        self.wallet.clone()
+    }
+
+    pub fn stake(&self) -> BigInt {
+        self.stake.clone()
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(self.wallet.as_bytes());
+        hasher.update(self.stake.to_string().as_bytes());
+        hasher.finalize().into()
+    }
+}
+
+pub fn total_stake(stakes: &[Stake]) -> BigInt {
+    let mut total_stake = BigInt::zero();
+    for stake in stakes {
+        total_stake += stake.stake();
+    }
+    total_stake
+}
+
+pub fn stakes_root(stakes: &[Stake]) -> [u8; 32] {
+    let hashes: Vec<[u8; 32]> = stakes.iter().map(|stake| stake.hash()).collect();
+    let merkle_tree = rs_merkle::MerkleTree::<rs_merkle::algorithms::Sha256>::from_leaves(&hashes);
+    merkle_tree.root().unwrap_or_default()
+}
+
+// Stakes must be sorted by wallet, every node has to pick the same validator.
+pub fn select_validator(parent_hash: &str, stakes: &[Stake]) -> Option<String> {
+    let total_stake = total_stake(stakes);
+    if total_stake.is_zero() {
+        return None;
+    }
+    let mut hasher = sha2::Sha256::new();
+    hasher.update(hex::decode(parent_hash).ok()?);
+    hasher.update(stakes_root(stakes));
+    let hash: [u8; 32] = hasher.finalize().into();
+    let seed = u64::from_be_bytes(hash[..8].try_into().unwrap());
+    let index = BigInt::from(seed) % total_stake;
+
+    let mut latest = BigInt::zero();
+    for stake in stakes {
+        latest += stake.stake();
+        if latest > index {
+            return Some(stake.wallet());
+        }
+    }
+    None
+}
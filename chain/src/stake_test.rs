@@ -0,0 +1,38 @@
+use crate::stake::{Stake, select_validator};
+use crate::system::MINIMUM_STAKE;
+use bigdecimal::num_bigint::BigInt;
+
+fn stake(wallet: &str, amount: u64) -> Stake {
+    Stake::new(String::from(wallet), BigInt::from(amount)).unwrap()
+}
+
+#[test]
+fn test_minimum_stake() {
+    assert!(Stake::new(String::from("a"), BigInt::from(MINIMUM_STAKE - 1)).is_none());
+    assert!(Stake::new(String::from("a"), BigInt::from(MINIMUM_STAKE)).is_some());
+}
+
+#[test]
+fn test_select_validator() {
+    let parent = hex::encode([7u8; 32]);
+    assert_eq!(select_validator(&parent, &[]), None);
+    assert_eq!(select_validator("not hex", &[stake("a", 10)]), None);
+    assert_eq!(
+        select_validator(&parent, &[stake("a", 10)]),
+        Some(String::from("a"))
+    );
+
+    let stakes = vec![stake("a", 10), stake("b", 20), stake("c", 30)];
+    let selected = select_validator(&parent, &stakes).unwrap();
+    assert_eq!(select_validator(&parent, &stakes), Some(selected.clone()));
+    assert!(["a", "b", "c"].contains(&selected.as_str()));
+}
+
+#[test]
+fn test_select_validator_by_weight() {
+    let stakes = vec![stake("a", 10), stake("b", 990)];
+    let picked = (0u8..100)
+        .filter(|seed| select_validator(&hex::encode([*seed; 32]), &stakes).as_deref() == Some("b"))
+        .count();
+    assert!(picked > 80);
+}
@@ -0,0 +1,205 @@
+use crate::snapshot::AccountState;
+use crate::stake::{self, Stake};
+use crate::system::{STAKE_WALLET, UNSTAKE_WALLET};
+use crate::tx::Tx;
+use bigdecimal::num_bigint::{BigInt, ToBigInt};
+use bigdecimal::{BigDecimal, Zero};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxRejection {
+    Signature,
+    Nonce { expected: u64 },
+    Amount,
+    Balance { current: BigDecimal },
+    Stake,
+}
+
+impl TxRejection {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            TxRejection::Signature => "signature",
+            TxRejection::Nonce { .. } => "nonce",
+            TxRejection::Amount => "amount",
+            TxRejection::Balance { .. } => "balance",
+            TxRejection::Stake => "stake",
+        }
+    }
+}
+
+impl fmt::Display for TxRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TxRejection::Signature => write!(f, "Invalid transaction signature"),
+            TxRejection::Nonce { expected } => {
+                write!(f, "Invalid nonce value, expected: {}", expected)
+            }
+            TxRejection::Amount => write!(
+                f,
+                "The amount must be positive, staked amounts must be integers"
+            ),
+            TxRejection::Balance { current } => {
+                write!(f, "Not enough balance, current: {}", current)
+            }
+            TxRejection::Stake => write!(f, "Not enough stake"),
+        }
+    }
+}
+
+impl std::error::Error for TxRejection {}
+
+impl From<TxRejection> for std::io::Error {
+    fn from(rejection: TxRejection) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, rejection)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Account {
+    balance: BigDecimal,
+    stake: BigInt,
+    nonce: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct State {
+    accounts: BTreeMap<String, Account>,
+}
+
+impl State {
+    pub fn new(accounts: Vec<AccountState>) -> Result<Self, std::io::Error> {
+        let mut state = Self::default();
+        for account in accounts {
+            let balance = BigDecimal::from_str(&account.balance).map_err(Self::invalid)?;
+            let stake = BigInt::from_str(&account.stake).map_err(Self::invalid)?;
+            state.accounts.insert(
+                account.address,
+                Account {
+                    balance,
+                    stake,
+                    nonce: account.nonce,
+                },
+            );
+        }
+        Ok(state)
+    }
+
+    fn invalid(error: impl ToString) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string())
+    }
+
+    pub fn account(&self, address: &str) -> AccountState {
+        let account = self.accounts.get(address).cloned().unwrap_or_default();
+        AccountState {
+            address: address.to_string(),
+            balance: account.balance.to_string(),
+            stake: account.stake.to_string(),
+            nonce: account.nonce,
+        }
+    }
+
+    pub fn accounts(&self) -> Vec<AccountState> {
+        self.accounts
+            .keys()
+            .map(|address| self.account(address))
+            .collect()
+    }
+
+    pub fn balance(&self, address: &str) -> BigDecimal {
+        self.accounts
+            .get(address)
+            .map(|account| account.balance.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn stake(&self, address: &str) -> BigInt {
+        self.accounts
+            .get(address)
+            .map(|account| account.stake.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn nonce(&self, address: &str) -> u64 {
+        self.accounts
+            .get(address)
+            .map(|account| account.nonce)
+            .unwrap_or_default()
+    }
+
+    pub fn check(&self, tx: &Tx) -> Result<(), TxRejection> {
+        if !tx.valid() {
+            return Err(TxRejection::Signature);
+        }
+        let expected = self.nonce(&tx.from()) + 1;
+        if tx.nonce() != expected {
+            return Err(TxRejection::Nonce { expected });
+        }
+        let amount = tx.amount();
+        if amount <= BigDecimal::zero() {
+            return Err(TxRejection::Amount);
+        }
+        match tx.to().as_str() {
+            STAKE_WALLET | UNSTAKE_WALLET if !amount.is_integer() => Err(TxRejection::Amount),
+            UNSTAKE_WALLET if self.stake(&tx.from()) < amount.to_bigint().unwrap() => {
+                Err(TxRejection::Stake)
+            }
+            UNSTAKE_WALLET => Ok(()),
+            _ => {
+                let current = self.balance(&tx.from());
+                if current < amount {
+                    return Err(TxRejection::Balance { current });
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn apply(&mut self, tx: &Tx) {
+        let amount = tx.amount();
+        let from = self.accounts.entry(tx.from()).or_default();
+        from.nonce = from.nonce.max(tx.nonce());
+        match tx.to().as_str() {
+            STAKE_WALLET => {
+                from.balance -= amount.clone();
+                from.stake += amount.to_bigint().unwrap_or_default();
+            }
+            UNSTAKE_WALLET => {
+                from.balance += amount.clone();
+                from.stake -= amount.to_bigint().unwrap_or_default();
+            }
+            _ => from.balance -= amount.clone(),
+        }
+        self.accounts.entry(tx.to()).or_default().balance += amount;
+    }
+
+    pub fn apply_checked(&mut self, tx: &Tx) -> Result<(), TxRejection> {
+        self.check(tx)?;
+        self.apply(tx);
+        Ok(())
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        let hashes: Vec<[u8; 32]> = self
+            .accounts()
+            .iter()
+            .map(|account| account.hash())
+            .collect();
+        let merkle_tree =
+            rs_merkle::MerkleTree::<rs_merkle::algorithms::Sha256>::from_leaves(&hashes);
+        merkle_tree.root().unwrap_or_default()
+    }
+
+    // Sorted by wallet, as validator selection expects.
+    pub fn stakes(&self) -> Vec<Stake> {
+        self.accounts
+            .iter()
+            .filter_map(|(address, account)| Stake::new(address.clone(), account.stake.clone()))
+            .collect()
+    }
+
+    pub fn validators_root(&self) -> [u8; 32] {
+        stake::stakes_root(&self.stakes())
+    }
+}
@@ -0,0 +1,58 @@
+use crate::snapshot::AccountState;
+use crate::state::{State, TxRejection};
+use crate::system::{STAKE_WALLET, UNSTAKE_WALLET};
+use crate::tx::Tx;
+use bigdecimal::BigDecimal;
+use wallet::wallet::Wallet;
+
+fn funded(wallet: &Wallet) -> State {
+    State::new(vec![AccountState {
+        address: wallet.address(),
+        balance: String::from("100"),
+        stake: String::from("0"),
+        nonce: 1,
+    }])
+    .unwrap()
+}
+
+#[test]
+fn test_apply_checked() {
+    let wallet = Wallet::new();
+    let mut state = funded(&wallet);
+    let tx = Tx::new(&wallet, String::from("to"), String::from("30"), 2).unwrap();
+    state.apply_checked(&tx).unwrap();
+    let stake = Tx::new(&wallet, String::from(STAKE_WALLET), String::from("50"), 3).unwrap();
+    state.apply_checked(&stake).unwrap();
+    let unstake = Tx::new(&wallet, String::from(UNSTAKE_WALLET), String::from("20"), 4).unwrap();
+    state.apply_checked(&unstake).unwrap();
+
+    assert_eq!(state.balance(&wallet.address()), BigDecimal::from(40));
+    assert_eq!(state.balance("to"), BigDecimal::from(30));
+    assert_eq!(state.stake(&wallet.address()), 30.into());
+    assert_eq!(state.nonce(&wallet.address()), 4);
+    assert_eq!(state.stakes().len(), 1);
+}
+
+#[test]
+fn test_rejections() {
+    let wallet = Wallet::new();
+    let state = funded(&wallet);
+    let gap = Tx::new(&wallet, String::from("to"), String::from("1"), 3).unwrap();
+    assert_eq!(state.check(&gap), Err(TxRejection::Nonce { expected: 2 }));
+    let spend = Tx::new(&wallet, String::from("to"), String::from("101"), 2).unwrap();
+    assert_eq!(
+        state.check(&spend),
+        Err(TxRejection::Balance {
+            current: BigDecimal::from(100)
+        })
+    );
+    let negative = Tx::new(&wallet, String::from("to"), String::from("-1"), 2).unwrap();
+    assert_eq!(state.check(&negative), Err(TxRejection::Amount));
+    let fraction = Tx::new(&wallet, String::from(STAKE_WALLET), String::from("1.5"), 2).unwrap();
+    assert_eq!(state.check(&fraction), Err(TxRejection::Amount));
+    let unstake = Tx::new(&wallet, String::from(UNSTAKE_WALLET), String::from("1"), 2).unwrap();
+    assert_eq!(state.check(&unstake), Err(TxRejection::Stake));
+    let mut forged = Tx::new(&wallet, String::from("to"), String::from("1"), 2).unwrap();
+    forged.amount = String::from("2");
+    assert_eq!(state.check(&forged), Err(TxRejection::Signature));
+}
@@ -0,0 +1,4 @@
+pub const GENESIS_WALLET: &str = "GENESIS";
+pub const STAKE_WALLET: &str = "STAKE";
+pub const UNSTAKE_WALLET: &str = "UNSTAKE";
+pub const MINIMUM_STAKE: u64 = 10;
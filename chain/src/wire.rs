@@ -0,0 +1,218 @@
+use crate::block::Block;
+use crate::proof::{AccountProof, TxProof};
+use crate::receipt::Receipt;
+use crate::snapshot::{SnapshotManifest, StateChunk};
+use crate::stake::Stake;
+use crate::tx::Tx;
+use serde::{Deserialize, Serialize};
+
+pub const STATUS_VERSION: &str = "0.0.1";
+pub const DEFAULT_REQUEST_TIMEOUT: u64 = 10;
+pub const DEFAULT_REQUEST_RETRIES: u32 = 3;
+pub const HEADERS_BATCH: u64 = 64;
+
+pub struct Protocol {
+    pub name: &'static str,
+    pub versions: &'static [&'static str],
+}
+
+pub const NONCE: Protocol = Protocol {
+    name: "nonce",
+    versions: &["0.0.1"],
+};
+pub const TX: Protocol = Protocol {
+    name: "tx",
+    versions: &["0.0.1"],
+};
+pub const BLOCK: Protocol = Protocol {
+    name: "block",
+    versions: &["0.0.2", "0.0.1"],
+};
+pub const BLOCKS: Protocol = Protocol {
+    name: "blocks",
+    versions: &["0.0.1"],
+};
+pub const SNAPSHOT: Protocol = Protocol {
+    name: "snapshot",
+    versions: &["0.0.2"],
+};
+pub const HISTORY: Protocol = Protocol {
+    name: "history",
+    versions: &["0.0.1"],
+};
+pub const STATUS: Protocol = Protocol {
+    name: "status",
+    versions: &[STATUS_VERSION],
+};
+pub const MEMPOOL: Protocol = Protocol {
+    name: "mempool",
+    versions: &["0.0.1"],
+};
+pub const ACCOUNT: Protocol = Protocol {
+    name: "account",
+    versions: &["0.0.1"],
+};
+
+impl Protocol {
+    pub fn paths(&self) -> Vec<String> {
+        self.versions
+            .iter()
+            .map(|version| format!("/{}/{}", self.name, version))
+            .collect()
+    }
+
+    pub fn supports(&self, version: &str) -> bool {
+        self.versions.contains(&version)
+    }
+
+    // Versions differing only in the patch number are wire compatible.
+    pub fn compatible(&self, version: &str) -> bool {
+        parse_version(version).is_some_and(|(major, minor, _)| {
+            self.versions
+                .iter()
+                .filter_map(|local| parse_version(local))
+                .any(|local| (local.0, local.1) == (major, minor))
+        })
+    }
+}
+
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    let parsed = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(parsed)
+}
+
+// Whether a message introduced in `since` can be sent over `version`.
+pub fn carries(version: &str, since: &str) -> bool {
+    match (parse_version(version), parse_version(since)) {
+        (Some(version), Some(since)) => version >= since,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NonceRequest {
+    pub address: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NonceResponse {
+    pub nonce: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxResponse {
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountRequest {
+    pub address: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BlockRequest {
+    ByIdx { idx: u64 },
+    ByHash { hash: String },
+    Headers { from: u64, count: u64 },
+    TxByHash { hash: String },
+    Receipt { hash: String },
+    AccountTxs { address: String },
+    AccountProof { address: String, idx: u64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BlockResponse {
+    Block(Option<Block>),
+    Headers(Vec<Block>),
+    Tx(Option<TxProof>),
+    Receipt(Option<Receipt>),
+    AccountTxs(Option<Vec<TxProof>>),
+    AccountProof(Option<AccountProof>),
+}
+
+impl BlockRequest {
+    pub fn since(&self) -> &'static str {
+        match self {
+            BlockRequest::ByIdx { .. } => "0.0.1",
+            BlockRequest::ByHash { .. }
+            | BlockRequest::Headers { .. }
+            | BlockRequest::TxByHash { .. }
+            | BlockRequest::Receipt { .. }
+            | BlockRequest::AccountTxs { .. }
+            | BlockRequest::AccountProof { .. } => "0.0.2",
+        }
+    }
+}
+
+impl BlockResponse {
+    pub fn since(&self) -> &'static str {
+        match self {
+            BlockResponse::Block(_) => "0.0.1",
+            BlockResponse::Headers(_)
+            | BlockResponse::Tx(_)
+            | BlockResponse::Receipt(_)
+            | BlockResponse::AccountTxs(_)
+            | BlockResponse::AccountProof(_) => "0.0.2",
+        }
+    }
+}
+
+// `/block/0.0.1` looked blocks up by index only.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyBlockRequest {
+    pub idx: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyBlockResponse {
+    pub block: Option<Block>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlocksRequest {
+    pub from: u64,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlocksResponse {
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SnapshotRequest {
+    Manifest,
+    Chunk { height: u64, idx: u64 },
+    Validators { idx: u64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SnapshotResponse {
+    Manifest(Option<Box<SnapshotManifest>>),
+    Chunk(Option<StateChunk>),
+    Validators(Option<Vec<Stake>>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MempoolRequest {
+    Inventory,
+    Txs { hashes: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MempoolResponse {
+    Inventory(Vec<String>),
+    Txs(Vec<Tx>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Status {
+    pub version: String,
+    pub chain_id: String,
+    pub genesis_hash: String,
+    pub head_height: u64,
+    pub head_hash: String,
+    pub finalized_height: u64,
+    pub earliest_body: u64,
+}
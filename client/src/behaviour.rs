@@ -0,0 +1,20 @@
+use chain::snapshot::AccountState;
+use chain::tx::Tx;
+use chain::wire::{
+    AccountRequest, BlockRequest, BlockResponse, NonceRequest, NonceResponse, SnapshotRequest,
+    SnapshotResponse, TxResponse,
+};
+use libp2p::allow_block_list;
+use libp2p::swarm::NetworkBehaviour;
+use libp2p::swarm::behaviour::toggle::Toggle;
+use network::protocol;
+
+#[derive(NetworkBehaviour)]
+pub struct ClientBehaviour {
+    pub allowed_peers: Toggle<allow_block_list::Behaviour<allow_block_list::AllowedPeers>>,
+    pub nonce: protocol::Behaviour<NonceRequest, NonceResponse>,
+    pub tx: protocol::Behaviour<Tx, TxResponse>,
+    pub find_block: protocol::Behaviour<BlockRequest, BlockResponse>,
+    pub snapshot: protocol::Behaviour<SnapshotRequest, SnapshotResponse>,
+    pub account: protocol::Behaviour<AccountRequest, AccountState>,
+}
@@ -0,0 +1,614 @@
+use crate::behaviour::{ClientBehaviour, ClientBehaviourEvent};
+use chain::block::Block;
+use chain::proof::{AccountProof, TxProof};
+use chain::receipt::{Receipt, TxStatus};
+use chain::snapshot::{AccountState, SnapshotManifest, StateChunk};
+use chain::stake::Stake;
+use chain::tx::Tx;
+use chain::wire::{
+    self, AccountRequest, BlockRequest, BlockResponse, DEFAULT_REQUEST_RETRIES,
+    DEFAULT_REQUEST_TIMEOUT, NonceRequest, NonceResponse, SnapshotRequest, SnapshotResponse,
+    TxResponse,
+};
+use futures::StreamExt;
+use libp2p::swarm::behaviour::toggle::Toggle;
+use libp2p::swarm::dial_opts::DialOpts;
+use libp2p::swarm::{ConnectionId, SwarmEvent};
+use libp2p::{Multiaddr, PeerId, Swarm, allow_block_list, noise, request_response, tcp, yamux};
+use network::private_network;
+use network::protocol::ProtocolExt;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+use tokio::select;
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+use wallet::wallet::Wallet;
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    pub nodes: Vec<String>,
+    pub request_timeout: u64,
+    pub request_retries: u32,
+    pub psk_path: Option<String>,
+    pub allowed_validators: Vec<String>,
+}
+
+impl ClientConfig {
+    pub fn new(nodes: Vec<String>) -> Self {
+        Self {
+            nodes,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            request_retries: DEFAULT_REQUEST_RETRIES,
+            psk_path: None,
+            allowed_validators: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientError {
+    NoNodes,
+    InvalidAddress(String),
+    Unreachable,
+    Timeout,
+    Transport(String),
+    Rejected(String),
+    InvalidTx(String),
+    UnexpectedResponse,
+    Unverified(String),
+    Closed,
+}
+
+impl ClientError {
+    pub fn retryable(&self) -> bool {
+        matches!(
+            self,
+            ClientError::Unreachable | ClientError::Timeout | ClientError::Transport(_)
+        )
+    }
+}
+
+impl fmt::Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::NoNodes => write!(f, "No nodes provided"),
+            ClientError::InvalidAddress(address) => write!(f, "Invalid node address: {}", address),
+            ClientError::Unreachable => write!(f, "No node is reachable"),
+            ClientError::Timeout => write!(f, "Request timed out"),
+            ClientError::Transport(e) => write!(f, "Request failed: {}", e),
+            ClientError::Rejected(e) => write!(f, "Rejected by node: {}", e),
+            ClientError::InvalidTx(e) => write!(f, "Cannot create tx: {}", e),
+            ClientError::UnexpectedResponse => write!(f, "Unexpected response"),
+            ClientError::Unverified(e) => write!(f, "Verification failed: {}", e),
+            ClientError::Closed => write!(f, "Client is closed"),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<request_response::OutboundFailure> for ClientError {
+    fn from(failure: request_response::OutboundFailure) -> Self {
+        match failure {
+            request_response::OutboundFailure::Timeout => ClientError::Timeout,
+            e => ClientError::Transport(e.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Request {
+    Nonce(NonceRequest),
+    Tx(Tx),
+    Block(BlockRequest),
+    Snapshot(SnapshotRequest),
+    Account(AccountRequest),
+}
+
+#[derive(Debug)]
+enum Response {
+    Nonce(NonceResponse),
+    Tx(TxResponse),
+    Block(Box<BlockResponse>),
+    Snapshot(SnapshotResponse),
+    Account(AccountState),
+}
+
+type Reply = oneshot::Sender<Result<Response, ClientError>>;
+
+enum Command {
+    Send { request: Request, reply: Reply },
+    Failover,
+}
+
+pub struct Client {
+    commands: mpsc::Sender<Command>,
+    timeout: Duration,
+    retries: u32,
+}
+
+impl Client {
+    pub async fn new(config: &ClientConfig) -> Result<Self, ClientError> {
+        if config.nodes.is_empty() {
+            return Err(ClientError::NoNodes);
+        }
+        let mut nodes = Vec::new();
+        for node in &config.nodes {
+            let address: Multiaddr = node
+                .parse()
+                .map_err(|_| ClientError::InvalidAddress(node.clone()))?;
+            nodes.push(address);
+        }
+        let swarm = Self::build_swarm(config).map_err(|e| ClientError::Transport(e.to_string()))?;
+        let (commands, receiver) = mpsc::channel(32);
+        tokio::spawn(EventLoop::new(swarm, nodes).run(receiver));
+        Ok(Self {
+            commands,
+            timeout: Duration::from_secs(config.request_timeout),
+            retries: config.request_retries,
+        })
+    }
+
+    fn build_swarm(config: &ClientConfig) -> Result<Swarm<ClientBehaviour>, Box<dyn Error>> {
+        let allowed_peers = private_network::allowed_peers(&config.allowed_validators)?;
+        let timeout = Duration::from_secs(config.request_timeout);
+        let builder = libp2p::SwarmBuilder::with_new_identity().with_tokio();
+        let swarm = match &config.psk_path {
+            Some(psk_path) => {
+                let psk = private_network::read_psk(psk_path)?;
+                builder
+                    .with_other_transport(|key| private_network::transport(key, psk))?
+                    .with_behaviour(|_| Self::build_behaviour(&allowed_peers, timeout))?
+                    .build()
+            }
+            None => builder
+                .with_tcp(
+                    tcp::Config::default(),
+                    noise::Config::new,
+                    yamux::Config::default,
+                )?
+                .with_behaviour(|_| Self::build_behaviour(&allowed_peers, timeout))?
+                .build(),
+        };
+        Ok(swarm)
+    }
+
+    fn build_behaviour(allowed_peers: &[PeerId], timeout: Duration) -> ClientBehaviour {
+        let config = request_response::Config::default().with_request_timeout(timeout);
+        ClientBehaviour {
+            allowed_peers: Toggle::from((!allowed_peers.is_empty()).then(|| {
+                let mut allowed = allow_block_list::Behaviour::default();
+                for peer in allowed_peers {
+                    allowed.allow_peer(*peer);
+                }
+                allowed
+            })),
+            nonce: wire::NONCE.behaviour_with_config(config.clone()),
+            tx: wire::TX.behaviour_with_config(config.clone()),
+            find_block: wire::BLOCK.behaviour_with_config(config.clone()),
+            snapshot: wire::SNAPSHOT.behaviour_with_config(config.clone()),
+            account: wire::ACCOUNT.behaviour_with_config(config),
+        }
+    }
+
+    pub async fn get_nonce(&self, address: String) -> Result<u64, ClientError> {
+        match self
+            .request(Request::Nonce(NonceRequest { address }))
+            .await?
+        {
+            Response::Nonce(response) => Ok(response.nonce),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_account(&self, address: String) -> Result<AccountState, ClientError> {
+        match self
+            .request(Request::Account(AccountRequest { address }))
+            .await?
+        {
+            Response::Account(account) => Ok(account),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_block(&self, idx: u64) -> Result<Option<Block>, ClientError> {
+        match self.find_block(BlockRequest::ByIdx { idx }).await? {
+            BlockResponse::Block(block) => Ok(block),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_block_by_hash(&self, hash: String) -> Result<Option<Block>, ClientError> {
+        match self.find_block(BlockRequest::ByHash { hash }).await? {
+            BlockResponse::Block(block) => Ok(block),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_headers(&self, from: u64, count: u64) -> Result<Vec<Block>, ClientError> {
+        match self
+            .find_block(BlockRequest::Headers { from, count })
+            .await?
+        {
+            BlockResponse::Headers(headers) => Ok(headers),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_tx(&self, hash: String) -> Result<Option<TxProof>, ClientError> {
+        match self.find_block(BlockRequest::TxByHash { hash }).await? {
+            BlockResponse::Tx(proof) => Ok(proof),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_receipt(&self, hash: String) -> Result<Option<Receipt>, ClientError> {
+        match self.find_block(BlockRequest::Receipt { hash }).await? {
+            BlockResponse::Receipt(receipt) => Ok(receipt),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_account_txs(
+        &self,
+        address: String,
+    ) -> Result<Option<Vec<TxProof>>, ClientError> {
+        match self
+            .find_block(BlockRequest::AccountTxs { address })
+            .await?
+        {
+            BlockResponse::AccountTxs(proofs) => Ok(proofs),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_account_proof(
+        &self,
+        address: String,
+        idx: u64,
+    ) -> Result<Option<AccountProof>, ClientError> {
+        match self
+            .find_block(BlockRequest::AccountProof { address, idx })
+            .await?
+        {
+            BlockResponse::AccountProof(proof) => Ok(proof),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    // A retry only resends the tx if the node has no receipt for it, the first attempt may
+    // have been accepted before its response was lost.
+    pub async fn send_tx(&self, tx: &Tx) -> Result<(), ClientError> {
+        let mut error = ClientError::Unreachable;
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                warn!("{}, retrying ({}/{})", error, attempt, self.retries);
+                let receipt = self.get_receipt(tx.hash_str()).await?;
+                if receipt.is_some_and(|receipt| receipt.status != TxStatus::Dropped) {
+                    return Ok(());
+                }
+            }
+            error = match self.attempt(Request::Tx(tx.clone())).await {
+                Ok(Response::Tx(TxResponse { error: None })) => return Ok(()),
+                Ok(Response::Tx(TxResponse { error: Some(e) })) => {
+                    return Err(ClientError::Rejected(e));
+                }
+                Ok(_) => return Err(ClientError::UnexpectedResponse),
+                Err(e) if e.retryable() => e,
+                Err(e) => return Err(e),
+            };
+        }
+        Err(error)
+    }
+
+    pub async fn transfer(
+        &self,
+        wallet: &Wallet,
+        to: String,
+        amount: String,
+    ) -> Result<Tx, ClientError> {
+        let nonce = self.get_nonce(wallet.address()).await?;
+        let tx = Tx::new(wallet, to, amount, nonce + 1)
+            .map_err(|e| ClientError::InvalidTx(e.to_string()))?;
+        self.send_tx(&tx).await?;
+        Ok(tx)
+    }
+
+    pub async fn get_snapshot_manifest(&self) -> Result<Option<SnapshotManifest>, ClientError> {
+        match self
+            .request(Request::Snapshot(SnapshotRequest::Manifest))
+            .await?
+        {
+            Response::Snapshot(SnapshotResponse::Manifest(manifest)) => Ok(manifest.map(|m| *m)),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_snapshot_chunk(
+        &self,
+        height: u64,
+        idx: u64,
+    ) -> Result<Option<StateChunk>, ClientError> {
+        match self
+            .request(Request::Snapshot(SnapshotRequest::Chunk { height, idx }))
+            .await?
+        {
+            Response::Snapshot(SnapshotResponse::Chunk(chunk)) => Ok(chunk),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    pub async fn get_validators(&self, idx: u64) -> Result<Option<Vec<Stake>>, ClientError> {
+        match self
+            .request(Request::Snapshot(SnapshotRequest::Validators { idx }))
+            .await?
+        {
+            Response::Snapshot(SnapshotResponse::Validators(stakes)) => Ok(stakes),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    async fn find_block(&self, request: BlockRequest) -> Result<BlockResponse, ClientError> {
+        match self.request(Request::Block(request)).await? {
+            Response::Block(response) => Ok(*response),
+            _ => Err(ClientError::UnexpectedResponse),
+        }
+    }
+
+    async fn request(&self, request: Request) -> Result<Response, ClientError> {
+        let mut error = ClientError::Unreachable;
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                warn!("{}, retrying ({}/{})", error, attempt, self.retries);
+            }
+            error = match self.attempt(request.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(e) if e.retryable() => e,
+                Err(e) => return Err(e),
+            };
+        }
+        Err(error)
+    }
+
+    async fn attempt(&self, request: Request) -> Result<Response, ClientError> {
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .send(Command::Send { request, reply })
+            .await
+            .map_err(|_| ClientError::Closed)?;
+        match tokio::time::timeout(self.timeout, response).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(ClientError::Closed),
+            Err(_) => {
+                let _ = self.commands.send(Command::Failover).await;
+                Err(ClientError::Timeout)
+            }
+        }
+    }
+}
+
+struct EventLoop {
+    swarm: Swarm<ClientBehaviour>,
+    nodes: Vec<Multiaddr>,
+    next_node: usize,
+    failed_dials: usize,
+    dial: Option<ConnectionId>,
+    abandoned: HashSet<ConnectionId>,
+    peer: Option<PeerId>,
+    queued: Vec<(Request, Reply)>,
+    pending: HashMap<request_response::OutboundRequestId, Reply>,
+}
+
+impl EventLoop {
+    fn new(swarm: Swarm<ClientBehaviour>, nodes: Vec<Multiaddr>) -> Self {
+        Self {
+            swarm,
+            nodes,
+            next_node: 0,
+            failed_dials: 0,
+            dial: None,
+            abandoned: HashSet::new(),
+            peer: None,
+            queued: Vec::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    async fn run(mut self, mut commands: mpsc::Receiver<Command>) {
+        loop {
+            select! {
+                command = commands.recv() => match command {
+                    Some(Command::Send { request, reply }) => self.send(request, reply),
+                    Some(Command::Failover) => self.failover(),
+                    None => return,
+                },
+                event = self.swarm.select_next_some() => self.handle_event(event),
+            }
+        }
+    }
+
+    fn send(&mut self, request: Request, reply: Reply) {
+        if reply.is_closed() {
+            return;
+        }
+        let Some(peer) = self.peer else {
+            self.queued.push((request, reply));
+            self.dial_next();
+            return;
+        };
+        let behaviour = self.swarm.behaviour_mut();
+        let request_id = match request {
+            Request::Nonce(request) => behaviour.nonce.send_request(&peer, request),
+            Request::Tx(request) => behaviour.tx.send_request(&peer, request),
+            Request::Block(request) => behaviour.find_block.send_request(&peer, request),
+            Request::Snapshot(request) => behaviour.snapshot.send_request(&peer, request),
+            Request::Account(request) => behaviour.account.send_request(&peer, request),
+        };
+        self.pending.insert(request_id, reply);
+    }
+
+    fn dial_next(&mut self) {
+        while self.dial.is_none() {
+            if self.failed_dials >= self.nodes.len() {
+                self.failed_dials = 0;
+                for (_, reply) in self.queued.drain(..) {
+                    let _ = reply.send(Err(ClientError::Unreachable));
+                }
+                return;
+            }
+            let address = self.nodes[self.next_node % self.nodes.len()].clone();
+            self.next_node += 1;
+            let opts = DialOpts::unknown_peer_id().address(address.clone()).build();
+            let connection_id = opts.connection_id();
+            match self.swarm.dial(opts) {
+                Ok(_) => self.dial = Some(connection_id),
+                Err(e) => {
+                    warn!("Cannot dial node {}: {}", address, e);
+                    self.failed_dials += 1;
+                }
+            }
+        }
+    }
+
+    // Requests whose caller gave up are not sent once a connection is up.
+    fn failover(&mut self) {
+        self.queued.retain(|(_, reply)| !reply.is_closed());
+        if let Some(connection_id) = self.dial.take() {
+            self.abandoned.insert(connection_id);
+            self.failed_dials += 1;
+            if !self.queued.is_empty() {
+                self.dial_next();
+            }
+        } else if let Some(peer) = self.peer.take() {
+            let _ = self.swarm.disconnect_peer_id(peer);
+        }
+    }
+
+    fn reply(&mut self, request_id: request_response::OutboundRequestId, response: Response) {
+        if let Some(reply) = self.pending.remove(&request_id) {
+            let _ = reply.send(Ok(response));
+        }
+    }
+
+    fn fail(
+        &mut self,
+        request_id: request_response::OutboundRequestId,
+        error: request_response::OutboundFailure,
+    ) {
+        if let Some(reply) = self.pending.remove(&request_id) {
+            let _ = reply.send(Err(ClientError::from(error)));
+        }
+        self.failover();
+    }
+
+    fn handle_event(&mut self, event: SwarmEvent<ClientBehaviourEvent>) {
+        match event {
+            SwarmEvent::ConnectionEstablished { connection_id, .. }
+                if self.abandoned.remove(&connection_id) =>
+            {
+                self.swarm.close_connection(connection_id);
+            }
+            SwarmEvent::OutgoingConnectionError { connection_id, .. }
+                if self.abandoned.remove(&connection_id) => {}
+            SwarmEvent::ConnectionEstablished { peer_id, .. } => {
+                self.dial = None;
+                self.failed_dials = 0;
+                self.peer = Some(peer_id);
+                for (request, reply) in std::mem::take(&mut self.queued) {
+                    self.send(request, reply);
+                }
+            }
+            SwarmEvent::OutgoingConnectionError { error, .. } => {
+                warn!("Cannot connect to node: {}", error);
+                self.dial = None;
+                self.failed_dials += 1;
+                if !self.queued.is_empty() {
+                    self.dial_next();
+                }
+            }
+            SwarmEvent::ConnectionClosed {
+                peer_id,
+                num_established: 0,
+                ..
+            } if self.peer == Some(peer_id) => self.peer = None,
+            SwarmEvent::Behaviour(ClientBehaviourEvent::Nonce(
+                request_response::Event::Message {
+                    message:
+                        request_response::Message::Response {
+                            request_id,
+                            response,
+                        },
+                    ..
+                },
+            )) => self.reply(request_id, Response::Nonce(response)),
+            SwarmEvent::Behaviour(ClientBehaviourEvent::Tx(request_response::Event::Message {
+                message:
+                    request_response::Message::Response {
+                        request_id,
+                        response,
+                    },
+                ..
+            })) => self.reply(request_id, Response::Tx(response)),
+            SwarmEvent::Behaviour(ClientBehaviourEvent::Snapshot(
+                request_response::Event::Message {
+                    message:
+                        request_response::Message::Response {
+                            request_id,
+                            response,
+                        },
+                    ..
+                },
+            )) => self.reply(request_id, Response::Snapshot(response)),
+            SwarmEvent::Behaviour(ClientBehaviourEvent::FindBlock(
+                request_response::Event::Message {
+                    message:
+                        request_response::Message::Response {
+                            request_id,
+                            response,
+                        },
+                    ..
+                },
+            )) => self.reply(request_id, Response::Block(Box::new(response))),
+            SwarmEvent::Behaviour(ClientBehaviourEvent::Account(
+                request_response::Event::Message {
+                    message:
+                        request_response::Message::Response {
+                            request_id,
+                            response,
+                        },
+                    ..
+                },
+            )) => self.reply(request_id, Response::Account(response)),
+            SwarmEvent::Behaviour(
+                ClientBehaviourEvent::Nonce(request_response::Event::OutboundFailure {
+                    request_id,
+                    error,
+                    ..
+                })
+                | ClientBehaviourEvent::Tx(request_response::Event::OutboundFailure {
+                    request_id,
+                    error,
+                    ..
+                })
+                | ClientBehaviourEvent::Snapshot(request_response::Event::OutboundFailure {
+                    request_id,
+                    error,
+                    ..
+                })
+                | ClientBehaviourEvent::FindBlock(request_response::Event::OutboundFailure {
+                    request_id,
+                    error,
+                    ..
+                })
+                | ClientBehaviourEvent::Account(request_response::Event::OutboundFailure {
+                    request_id,
+                    error,
+                    ..
+                }),
+            ) => self.fail(request_id, error),
+            _ => {}
+        }
+    }
+}
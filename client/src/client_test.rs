@@ -0,0 +1,264 @@
+use crate::behaviour::{ClientBehaviour, ClientBehaviourEvent};
+use crate::client::{Client, ClientConfig, ClientError};
+use chain::receipt::{Receipt, TxStatus};
+use chain::snapshot::AccountState;
+use chain::tx::Tx;
+use chain::wire::{self, BlockRequest, BlockResponse, NonceResponse, TxResponse};
+use futures::StreamExt;
+use libp2p::identity::Keypair;
+use libp2p::pnet::PreSharedKey;
+use libp2p::swarm::SwarmEvent;
+use libp2p::swarm::behaviour::toggle::Toggle;
+use libp2p::{Multiaddr, Swarm, noise, request_response, tcp, yamux};
+use network::private_network;
+use network::protocol::ProtocolExt;
+use wallet::wallet::Wallet;
+
+const DEAD_NODE: &str = "/ip4/127.0.0.1/tcp/1";
+
+async fn start_node() -> Multiaddr {
+    start_private_node(Keypair::generate_secp256k1(), None).await
+}
+
+async fn start_private_node(keypair: Keypair, psk: Option<PreSharedKey>) -> Multiaddr {
+    let builder = libp2p::SwarmBuilder::with_existing_identity(keypair).with_tokio();
+    let behaviour = |_: &Keypair| ClientBehaviour {
+        allowed_peers: Toggle::from(None),
+        nonce: wire::NONCE.behaviour(),
+        tx: wire::TX.behaviour(),
+        find_block: wire::BLOCK.behaviour(),
+        snapshot: wire::SNAPSHOT.behaviour(),
+        account: wire::ACCOUNT.behaviour(),
+    };
+    let mut swarm: Swarm<ClientBehaviour> = match psk {
+        Some(psk) => builder
+            .with_other_transport(|key| private_network::transport(key, psk))
+            .unwrap()
+            .with_behaviour(behaviour)
+            .unwrap()
+            .build(),
+        None => builder
+            .with_tcp(
+                tcp::Config::default(),
+                noise::Config::new,
+                yamux::Config::default,
+            )
+            .unwrap()
+            .with_behaviour(behaviour)
+            .unwrap()
+            .build(),
+    };
+    swarm
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = swarm.select_next_some().await {
+            break address;
+        }
+    };
+    tokio::spawn(async move {
+        loop {
+            match swarm.select_next_some().await {
+                SwarmEvent::Behaviour(ClientBehaviourEvent::Nonce(
+                    request_response::Event::Message {
+                        message: request_response::Message::Request { channel, .. },
+                        ..
+                    },
+                )) => {
+                    let _ = swarm
+                        .behaviour_mut()
+                        .nonce
+                        .send_response(channel, NonceResponse { nonce: 7 });
+                }
+                SwarmEvent::Behaviour(ClientBehaviourEvent::Tx(
+                    request_response::Event::Message {
+                        message:
+                            request_response::Message::Request {
+                                request, channel, ..
+                            },
+                        ..
+                    },
+                )) => {
+                    // Accepted, but the response never reaches the client.
+                    if request.to() == "lost" {
+                        continue;
+                    }
+                    let _ = swarm.behaviour_mut().tx.send_response(
+                        channel,
+                        TxResponse {
+                            error: Some(String::from("Invalid nonce value")),
+                        },
+                    );
+                }
+                SwarmEvent::Behaviour(ClientBehaviourEvent::Account(
+                    request_response::Event::Message {
+                        message:
+                            request_response::Message::Request {
+                                request, channel, ..
+                            },
+                        ..
+                    },
+                )) => {
+                    let _ = swarm.behaviour_mut().account.send_response(
+                        channel,
+                        AccountState {
+                            address: request.address,
+                            balance: String::from("100"),
+                            stake: String::from("0"),
+                            nonce: 7,
+                        },
+                    );
+                }
+                SwarmEvent::Behaviour(ClientBehaviourEvent::FindBlock(
+                    request_response::Event::Message {
+                        message:
+                            request_response::Message::Request {
+                                request, channel, ..
+                            },
+                        ..
+                    },
+                )) => {
+                    let response = match request {
+                        BlockRequest::Receipt { hash } => {
+                            BlockResponse::Receipt(Some(Receipt::included(hash, 3, 0)))
+                        }
+                        _ => BlockResponse::Block(None),
+                    };
+                    let _ = swarm
+                        .behaviour_mut()
+                        .find_block
+                        .send_response(channel, response);
+                }
+                _ => {}
+            }
+        }
+    });
+    address
+}
+
+// Accepts TCP connections and never answers, so dials hang in the handshake.
+async fn start_silent_node() -> Multiaddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    tokio::spawn(async move {
+        let mut sockets = Vec::new();
+        while let Ok((socket, _)) = listener.accept().await {
+            sockets.push(socket);
+        }
+    });
+    format!("/ip4/127.0.0.1/tcp/{}", port).parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_client_fails_over_to_reachable_node() {
+    let address = start_node().await;
+    let silent = start_silent_node().await;
+    let config = ClientConfig {
+        nodes: vec![silent.to_string(), address.to_string()],
+        request_timeout: 1,
+        request_retries: 2,
+        ..ClientConfig::new(Vec::new())
+    };
+    let client = Client::new(&config).await.unwrap();
+    assert_eq!(client.get_nonce(String::from("address")).await, Ok(7));
+
+    let wallet = Wallet::new();
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 8).unwrap();
+    assert_eq!(
+        client.send_tx(&tx).await,
+        Err(ClientError::Rejected(String::from("Invalid nonce value")))
+    );
+    let tx = Tx::new(&wallet, String::from("lost"), String::from("10"), 8).unwrap();
+    assert_eq!(client.send_tx(&tx).await, Ok(()));
+}
+
+#[tokio::test]
+async fn test_client_errors() {
+    let config = ClientConfig::new(Vec::new());
+    assert_eq!(Client::new(&config).await.err(), Some(ClientError::NoNodes));
+
+    let invalid = ClientConfig::new(vec![String::from("localhost:8080")]);
+    assert_eq!(
+        Client::new(&invalid).await.err(),
+        Some(ClientError::InvalidAddress(String::from("localhost:8080")))
+    );
+
+    let unreachable = ClientConfig {
+        nodes: vec![String::from(DEAD_NODE)],
+        request_timeout: 5,
+        request_retries: 1,
+        ..ClientConfig::new(Vec::new())
+    };
+    let client = Client::new(&unreachable).await.unwrap();
+    assert_eq!(
+        client.get_nonce(String::from("address")).await,
+        Err(ClientError::Unreachable)
+    );
+}
+
+#[tokio::test]
+async fn test_client_queries() {
+    let address = start_node().await;
+    let client = Client::new(&ClientConfig::new(vec![address.to_string()]))
+        .await
+        .unwrap();
+    let account = client.get_account(String::from("address")).await.unwrap();
+    assert_eq!(account.balance, "100");
+    assert_eq!(account.nonce, 7);
+    let receipt = client
+        .get_receipt(String::from("hash"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(receipt.status, TxStatus::Included);
+    assert_eq!(receipt.block, Some(3));
+    assert_eq!(client.get_block(1).await, Ok(None));
+
+    let wallet = Wallet::new();
+    let result = client
+        .transfer(&wallet, String::from("to"), String::from("10"))
+        .await;
+    assert_eq!(
+        result,
+        Err(ClientError::Rejected(String::from("Invalid nonce value")))
+    );
+}
+
+#[tokio::test]
+async fn test_client_in_private_network() {
+    let keypair = Keypair::generate_secp256k1();
+    let validator = hex::encode(keypair.public().try_into_secp256k1().unwrap().to_bytes());
+    let psk = PreSharedKey::new([7u8; 32]);
+    let address = start_private_node(keypair, Some(psk)).await;
+    let temp_dir = tempfile::tempdir().unwrap();
+    let psk_path = temp_dir.path().join("swarm.key");
+    std::fs::write(&psk_path, psk.to_string()).unwrap();
+
+    let config = ClientConfig {
+        request_timeout: 2,
+        request_retries: 0,
+        psk_path: Some(psk_path.to_str().unwrap().to_string()),
+        allowed_validators: vec![validator],
+        ..ClientConfig::new(vec![address.to_string()])
+    };
+    let client = Client::new(&config).await.unwrap();
+    assert_eq!(client.get_nonce(String::from("address")).await, Ok(7));
+
+    let public = ClientConfig {
+        request_timeout: 2,
+        request_retries: 0,
+        ..ClientConfig::new(vec![address.to_string()])
+    };
+    let client = Client::new(&public).await.unwrap();
+    assert!(client.get_nonce(String::from("address")).await.is_err());
+
+    let other = Keypair::generate_secp256k1();
+    let config = ClientConfig {
+        allowed_validators: vec![hex::encode(
+            other.public().try_into_secp256k1().unwrap().to_bytes(),
+        )],
+        ..config
+    };
+    let client = Client::new(&config).await.unwrap();
+    assert!(client.get_nonce(String::from("address")).await.is_err());
+}
@@ -0,0 +1,7 @@
+pub mod behaviour;
+pub mod client;
+#[cfg(test)]
+mod client_test;
+pub mod light;
+#[cfg(test)]
+mod light_test;
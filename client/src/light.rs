@@ -0,0 +1,200 @@
+use crate::client::{Client, ClientError};
+use chain::block::Block;
+use chain::headers::HeaderChain;
+use chain::proof::TxProof;
+use chain::snapshot::AccountState;
+use chain::stake::Stake;
+use chain::state::State;
+use chain::wire::HEADERS_BATCH;
+use std::collections::HashSet;
+
+// Headers kept below the finalized one, older ones are dropped as the chain grows.
+pub const HEADERS_WINDOW: u64 = 1024;
+
+// How an account returned by the light client was checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verification {
+    // Proven against the state root of a synced header.
+    Verified { height: u64 },
+    // Computed from txs with inclusion proofs, the node may have left txs out.
+    Unverified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightAccount {
+    pub account: AccountState,
+    pub verification: Verification,
+}
+
+pub struct LightClient {
+    client: Client,
+    headers: HeaderChain,
+    finality_depth: u64,
+}
+
+impl LightClient {
+    pub async fn new(
+        client: Client,
+        genesis_hash: &str,
+        finality_depth: u64,
+    ) -> Result<Self, ClientError> {
+        let Some(genesis) = client.get_block(0).await? else {
+            return Err(ClientError::Unverified(String::from(
+                "Genesis block not found",
+            )));
+        };
+        if genesis.idx != 0 || genesis.hash_str() != genesis_hash {
+            return Err(ClientError::Unverified(format!(
+                "Unexpected genesis block {}",
+                genesis.hash_str()
+            )));
+        }
+        let headers = HeaderChain::genesis(&genesis).map_err(unverified)?;
+        Ok(Self {
+            client,
+            headers,
+            finality_depth,
+        })
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn head(&self) -> &Block {
+        self.headers.head()
+    }
+
+    pub fn height(&self) -> u64 {
+        self.headers.height()
+    }
+
+    pub fn finalized_height(&self) -> u64 {
+        self.height().saturating_sub(self.finality_depth)
+    }
+
+    pub fn header(&self, idx: u64) -> Option<&Block> {
+        self.headers.header(idx)
+    }
+
+    pub fn validators(&self) -> &[Stake] {
+        self.headers.stakes()
+    }
+
+    pub async fn sync(&mut self) -> Result<u64, ClientError> {
+        loop {
+            let headers = self
+                .client
+                .get_headers(self.height() + 1, HEADERS_BATCH)
+                .await?;
+            if headers.is_empty() {
+                return Ok(self.height());
+            }
+            for header in headers {
+                self.append(header).await?;
+            }
+            let keep_from = self.finalized_height().saturating_sub(HEADERS_WINDOW);
+            self.headers.truncate_below(keep_from);
+        }
+    }
+
+    // The header chain checks the signer against the validator selected from the committed
+    // stakes, a changed validator set is fetched and checked against the header.
+    async fn append(&mut self, header: Block) -> Result<(), ClientError> {
+        let stakes = if self.headers.validators_changed(&header) {
+            self.client.get_validators(header.idx).await?
+        } else {
+            None
+        };
+        self.headers.append(&header, stakes).map_err(unverified)
+    }
+
+    pub async fn verify_tx(&self, hash: String) -> Result<Option<TxProof>, ClientError> {
+        let Some(proof) = self.client.get_tx(hash.clone()).await? else {
+            return Ok(None);
+        };
+        if proof.tx.hash_str() != hash {
+            return Err(ClientError::Unverified(format!(
+                "Unexpected tx {}",
+                proof.tx.hash_str()
+            )));
+        }
+        self.verify_proof(&proof)?;
+        Ok(Some(proof))
+    }
+
+    pub async fn verify_account(&self, address: String) -> Result<LightAccount, ClientError> {
+        self.account_at(address, self.height()).await
+    }
+
+    async fn account_at(&self, address: String, height: u64) -> Result<LightAccount, ClientError> {
+        let Some(header) = self.header(height) else {
+            return Err(ClientError::Unverified(format!(
+                "Block {} is not synced",
+                height
+            )));
+        };
+        if let Some(proof) = self
+            .client
+            .get_account_proof(address.clone(), height)
+            .await?
+        {
+            if proof.account.address != address || !proof.verify_against(header) {
+                return Err(ClientError::Unverified(format!(
+                    "Invalid state proof of {}",
+                    address
+                )));
+            }
+            return Ok(LightAccount {
+                account: proof.account,
+                verification: Verification::Verified { height },
+            });
+        }
+        let Some(proofs) = self.client.get_account_txs(address.clone()).await? else {
+            return Err(ClientError::Unverified(format!(
+                "History of {} is not available",
+                address
+            )));
+        };
+        let mut hashes = HashSet::new();
+        let mut state = State::default();
+        for proof in proofs {
+            if proof.header.idx > height || !hashes.insert(proof.tx.hash_str()) {
+                continue;
+            }
+            if proof.tx.from() != address && proof.tx.to() != address {
+                return Err(ClientError::Unverified(format!(
+                    "Tx {} does not belong to {}",
+                    proof.tx.hash_str(),
+                    address
+                )));
+            }
+            self.verify_proof(&proof)?;
+            state.apply(&proof.tx);
+        }
+        Ok(LightAccount {
+            account: state.account(&address),
+            verification: Verification::Unverified,
+        })
+    }
+
+    fn verify_proof(&self, proof: &TxProof) -> Result<(), ClientError> {
+        let Some(header) = self.header(proof.header.idx) else {
+            return Err(ClientError::Unverified(format!(
+                "Block {} is not synced",
+                proof.header.idx
+            )));
+        };
+        if !proof.verify_against(header) {
+            return Err(ClientError::Unverified(format!(
+                "Invalid inclusion proof of tx {}",
+                proof.tx.hash_str()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn unverified(error: std::io::Error) -> ClientError {
+    ClientError::Unverified(error.to_string())
+}
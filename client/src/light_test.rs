@@ -0,0 +1,258 @@
+use crate::behaviour::{ClientBehaviour, ClientBehaviourEvent};
+use crate::client::{Client, ClientConfig, ClientError};
+use crate::light::{LightClient, Verification};
+use chain::block::Block;
+use chain::proof::{AccountProof, TxProof};
+use chain::state::State;
+use chain::system::STAKE_WALLET;
+use chain::tx::Tx;
+use chain::wire::{self, BlockRequest, BlockResponse};
+use futures::StreamExt;
+use libp2p::swarm::SwarmEvent;
+use libp2p::swarm::behaviour::toggle::Toggle;
+use libp2p::{Multiaddr, Swarm, noise, request_response, tcp, yamux};
+use network::protocol::ProtocolExt;
+use wallet::wallet::Wallet;
+
+fn proof(blocks: &[Block], idx: usize, tx: &Tx) -> TxProof {
+    let txs = blocks[idx].txs().unwrap_or_default();
+    let leaves: Vec<[u8; 32]> = txs.iter().map(|tx| tx.hash()).collect();
+    let mut tx = tx.clone();
+    tx.block = Some(idx as u64);
+    let mut header = blocks[idx].clone();
+    header.txs = None;
+    TxProof::new(tx, header, &leaves).unwrap()
+}
+
+fn account_proof(blocks: &[Block], address: &str, idx: u64) -> Option<AccountProof> {
+    let mut state = State::default();
+    for block in blocks.iter().take(idx as usize + 1) {
+        for tx in block.txs().unwrap_or_default() {
+            state.apply(&tx);
+        }
+    }
+    let mut header = blocks.get(idx as usize)?.clone();
+    header.txs = None;
+    AccountProof::new(address, header, &state.accounts())
+}
+
+#[derive(Clone, Copy)]
+struct Serve {
+    state_proofs: bool,
+    tamper: bool,
+}
+
+const HONEST: Serve = Serve {
+    state_proofs: true,
+    tamper: false,
+};
+
+fn respond(blocks: &[Block], request: BlockRequest, serve: Serve) -> BlockResponse {
+    match request {
+        BlockRequest::ByIdx { idx } => BlockResponse::Block(blocks.get(idx as usize).cloned()),
+        BlockRequest::Headers { from, count } => BlockResponse::Headers(
+            blocks
+                .iter()
+                .skip(from as usize)
+                .take(count as usize)
+                .map(|block| {
+                    let mut header = block.clone();
+                    header.txs = None;
+                    header
+                })
+                .collect(),
+        ),
+        BlockRequest::TxByHash { hash } => BlockResponse::Tx(
+            blocks
+                .iter()
+                .enumerate()
+                .flat_map(|(idx, block)| {
+                    block
+                        .txs()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(move |tx| (idx, tx))
+                })
+                .find(|(_, tx)| tx.hash_str() == hash)
+                .map(|(idx, tx)| proof(blocks, idx, &tx)),
+        ),
+        BlockRequest::AccountTxs { address } => {
+            let mut proofs = Vec::new();
+            for (idx, block) in blocks.iter().enumerate() {
+                for tx in block.txs().unwrap_or_default() {
+                    if tx.from() == address || tx.to() == address {
+                        let mut proof = proof(blocks, idx, &tx);
+                        if serve.tamper {
+                            proof.tx.amount = String::from("1000000");
+                        }
+                        proofs.push(proof);
+                    }
+                }
+            }
+            BlockResponse::AccountTxs(Some(proofs))
+        }
+        BlockRequest::AccountProof { address, idx } => BlockResponse::AccountProof(
+            account_proof(blocks, &address, idx).filter(|_| serve.state_proofs),
+        ),
+        _ => BlockResponse::Block(None),
+    }
+}
+
+async fn start_node(blocks: Vec<Block>, serve: Serve) -> Multiaddr {
+    let mut swarm: Swarm<ClientBehaviour> = libp2p::SwarmBuilder::with_new_identity()
+        .with_tokio()
+        .with_tcp(
+            tcp::Config::default(),
+            noise::Config::new,
+            yamux::Config::default,
+        )
+        .unwrap()
+        .with_behaviour(|_| ClientBehaviour {
+            allowed_peers: Toggle::from(None),
+            nonce: wire::NONCE.behaviour(),
+            tx: wire::TX.behaviour(),
+            find_block: wire::BLOCK.behaviour(),
+            snapshot: wire::SNAPSHOT.behaviour(),
+            account: wire::ACCOUNT.behaviour(),
+        })
+        .unwrap()
+        .build();
+    swarm
+        .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+        .unwrap();
+    let address = loop {
+        if let SwarmEvent::NewListenAddr { address, .. } = swarm.select_next_some().await {
+            break address;
+        }
+    };
+    tokio::spawn(async move {
+        loop {
+            if let SwarmEvent::Behaviour(ClientBehaviourEvent::FindBlock(
+                request_response::Event::Message {
+                    message:
+                        request_response::Message::Request {
+                            request, channel, ..
+                        },
+                    ..
+                },
+            )) = swarm.select_next_some().await
+            {
+                let response = respond(&blocks, request, serve);
+                let _ = swarm
+                    .behaviour_mut()
+                    .find_block
+                    .send_response(channel, response);
+            }
+        }
+    });
+    address
+}
+
+fn chain(validator: &Wallet, signer: &Wallet, user: &Wallet) -> (Vec<Block>, Tx) {
+    let genesis_txs = vec![
+        Tx::new(
+            validator,
+            String::from(STAKE_WALLET),
+            String::from("500"),
+            1,
+        )
+        .unwrap(),
+        Tx::new(validator, user.address(), String::from("100"), 2).unwrap(),
+    ];
+    let mut state = State::default();
+    for tx in &genesis_txs {
+        state.apply(tx);
+    }
+    let genesis = Block::genesis(genesis_txs);
+    let transfer = Tx::new(validator, user.address(), String::from("10"), 3).unwrap();
+    state.apply(&transfer);
+    let first = Block::new(
+        signer,
+        1,
+        genesis.hash_str(),
+        vec![transfer.clone()],
+        &state,
+    )
+    .unwrap();
+    let second = Block::new(signer, 2, first.hash_str(), Vec::new(), &state).unwrap();
+    (vec![genesis, first, second], transfer)
+}
+
+async fn light_client(address: Multiaddr, genesis: &Block) -> Result<LightClient, ClientError> {
+    let client = Client::new(&ClientConfig::new(vec![address.to_string()])).await?;
+    LightClient::new(client, &genesis.hash_str(), 1).await
+}
+
+#[tokio::test]
+async fn test_light_client_sync_and_verify() {
+    let validator = Wallet::new();
+    let user = Wallet::new();
+    let (blocks, transfer) = chain(&validator, &validator, &user);
+    let genesis = blocks[0].clone();
+    let address = start_node(blocks.clone(), HONEST).await;
+
+    let mut light = light_client(address.clone(), &genesis).await.unwrap();
+    assert_eq!(light.sync().await, Ok(2));
+    assert_eq!(light.finalized_height(), 1);
+    assert_eq!(light.validators().len(), 1);
+    assert_eq!(light.validators()[0].wallet(), validator.address());
+
+    let verified = light.verify_account(user.address()).await.unwrap();
+    assert_eq!(verified.verification, Verification::Verified { height: 2 });
+    assert_eq!(verified.account.balance, "110");
+    assert_eq!(verified.account.nonce, 0);
+    let verified = light.verify_account(validator.address()).await.unwrap();
+    assert_eq!(verified.account.stake, "500");
+    assert_eq!(verified.account.nonce, 3);
+
+    let proof = light.verify_tx(transfer.hash_str()).await.unwrap().unwrap();
+    assert_eq!(proof.header.idx, 1);
+    assert_eq!(light.verify_tx(String::from("missing")).await, Ok(None));
+
+    let mut other = genesis.clone();
+    other.timestamp += 1;
+    assert!(matches!(
+        light_client(address, &other).await.err(),
+        Some(ClientError::Unverified(_))
+    ));
+
+    let serve = Serve {
+        state_proofs: false,
+        tamper: false,
+    };
+    let address = start_node(blocks, serve).await;
+    let mut light = light_client(address, &genesis).await.unwrap();
+    light.sync().await.unwrap();
+    let unverified = light.verify_account(user.address()).await.unwrap();
+    assert_eq!(unverified.verification, Verification::Unverified);
+    assert_eq!(unverified.account.balance, "110");
+}
+
+#[tokio::test]
+async fn test_light_client_rejects_unverified_data() {
+    let validator = Wallet::new();
+    let user = Wallet::new();
+    let (blocks, _) = chain(&validator, &user, &user);
+    let genesis = blocks[0].clone();
+    let address = start_node(blocks, HONEST).await;
+    let mut light = light_client(address, &genesis).await.unwrap();
+    assert!(matches!(
+        light.sync().await,
+        Err(ClientError::Unverified(_))
+    ));
+    assert_eq!(light.height(), 0);
+
+    let (blocks, _) = chain(&validator, &validator, &user);
+    let genesis = blocks[0].clone();
+    let serve = Serve {
+        state_proofs: false,
+        tamper: true,
+    };
+    let address = start_node(blocks, serve).await;
+    let mut light = light_client(address, &genesis).await.unwrap();
+    light.sync().await.unwrap();
+    assert!(matches!(
+        light.verify_account(user.address()).await,
+        Err(ClientError::Unverified(_))
+    ));
+}
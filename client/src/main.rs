@@ -1,3 +1,185 @@
-fn main() {
-    println!("Hello, world!");
+use chain::headers::DEFAULT_FINALITY_DEPTH;
+use chain::wire::{DEFAULT_REQUEST_RETRIES, DEFAULT_REQUEST_TIMEOUT};
+use clap::{ArgGroup, Parser, Subcommand};
+use client::client::{Client, ClientConfig};
+use client::light::{LightClient, Verification};
+use tracing_subscriber::EnvFilter;
+use wallet::wallet::Wallet;
+
+#[derive(Parser)]
+#[command(version, about, long_about = "xhcg-blockchain client")]
+pub struct Cli {
+    #[arg(long = "node", value_name = "address", required = true)]
+    nodes: Vec<String>,
+    #[arg(long, value_name = "seconds", default_value_t = DEFAULT_REQUEST_TIMEOUT)]
+    timeout: u64,
+    #[arg(long, value_name = "count", default_value_t = DEFAULT_REQUEST_RETRIES)]
+    retries: u32,
+    #[arg(
+        long,
+        value_name = "path",
+        help = "Pre-shared key of a private network"
+    )]
+    psk: Option<String>,
+    #[arg(
+        long = "allowed-validator",
+        value_name = "address",
+        help = "Only connect to nodes of these validators"
+    )]
+    allowed_validators: Vec<String>,
+    #[arg(
+        long,
+        value_name = "genesis hash",
+        help = "Sync block headers from the given genesis and verify responses with merkle proofs"
+    )]
+    light: Option<String>,
+    #[arg(long, value_name = "blocks", default_value_t = DEFAULT_FINALITY_DEPTH)]
+    finality_depth: u64,
+    #[command(subcommand)]
+    cmd: ClientCmd,
+}
+
+#[derive(Subcommand)]
+pub enum ClientCmd {
+    #[clap(about = "Show balance, stake and nonce of an address")]
+    Account {
+        #[arg(long, value_name = "address")]
+        address: String,
+    },
+    #[clap(about = "Show a block by index or hash")]
+    #[command(group(ArgGroup::new("block").required(true).args(["idx", "hash"])))]
+    Block {
+        #[arg(long, value_name = "idx")]
+        idx: Option<u64>,
+        #[arg(long, value_name = "hash")]
+        hash: Option<String>,
+    },
+    #[clap(about = "Show a transaction with its merkle inclusion proof")]
+    Proof {
+        #[arg(long, value_name = "hash")]
+        hash: String,
+    },
+    #[clap(about = "Show status of a transaction")]
+    Status {
+        #[arg(long, value_name = "hash")]
+        hash: String,
+    },
+    #[clap(about = "Create, sign and submit a transaction")]
+    Tx {
+        #[arg(long, value_name = "path")]
+        keystore: String,
+        #[arg(long, value_name = "from")]
+        from: String,
+        #[arg(long, value_name = "to")]
+        to: String,
+        #[arg(long, value_name = "amount")]
+        amount: String,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or(EnvFilter::new("warn")))
+        .with_writer(std::io::stderr)
+        .init();
+    let config = ClientConfig {
+        nodes: cli.nodes,
+        request_timeout: cli.timeout,
+        request_retries: cli.retries,
+        psk_path: cli.psk,
+        allowed_validators: cli.allowed_validators,
+    };
+    let client = Client::new(&config).await?;
+    match cli.light {
+        Some(genesis_hash) => {
+            let mut light = LightClient::new(client, &genesis_hash, cli.finality_depth).await?;
+            println!("Synced headers up to block {}", light.sync().await?);
+            run(light.client(), Some(&light), cli.cmd).await
+        }
+        None => run(&client, None, cli.cmd).await,
+    }
+}
+
+async fn run(
+    client: &Client,
+    light: Option<&LightClient>,
+    cmd: ClientCmd,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        ClientCmd::Account { address } => {
+            let (account, verification) = match light {
+                Some(light) => {
+                    let verified = light.verify_account(address).await?;
+                    (verified.account, Some(verified.verification))
+                }
+                None => (client.get_account(address).await?, None),
+            };
+            println!("Balance: {}", account.balance);
+            println!("Stake: {}", account.stake);
+            println!("Nonce: {}", account.nonce);
+            match verification {
+                Some(Verification::Verified { height }) => {
+                    println!("Verified against the state root of block {}", height)
+                }
+                Some(Verification::Unverified) => {
+                    println!("Unverified, computed from the tx history served by the node")
+                }
+                None => {}
+            }
+        }
+        ClientCmd::Block { idx, hash } => {
+            let block = match hash {
+                Some(hash) => client.get_block_by_hash(hash).await?,
+                None => {
+                    client
+                        .get_block(idx.ok_or("Block index or hash is required")?)
+                        .await?
+                }
+            };
+            match block {
+                Some(block) => println!("{:?}", block),
+                None => println!("Block not found"),
+            }
+        }
+        ClientCmd::Proof { hash } => {
+            let proof = match light {
+                Some(light) => light.verify_tx(hash).await?,
+                None => client.get_tx(hash).await?,
+            };
+            match proof {
+                Some(proof) => {
+                    println!("{:?}", proof);
+                    match light {
+                        Some(light) => println!(
+                            "Verified against synced header {}, finalized: {}",
+                            proof.header.idx,
+                            proof.header.idx <= light.finalized_height()
+                        ),
+                        None => println!("Valid: {}", proof.verify()),
+                    }
+                }
+                None => println!("Transaction not found"),
+            }
+        }
+        ClientCmd::Status { hash } => match client.get_receipt(hash).await? {
+            Some(receipt) => println!("{:?}", receipt),
+            None => println!("Transaction not found"),
+        },
+        ClientCmd::Tx {
+            keystore,
+            from,
+            to,
+            amount,
+        } => {
+            println!("Enter password:");
+            let password = rpassword::read_password()?;
+            let wallet = Wallet::read(&keystore, from.as_str(), password.as_bytes())?;
+            let tx = client.transfer(&wallet, to, amount).await?;
+            println!("Tx created: {:?}", tx);
+            println!("Transaction successfully submitted");
+        }
+    }
+    Ok(())
 }
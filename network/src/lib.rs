@@ -0,0 +1,2 @@
+pub mod private_network;
+pub mod protocol;
@@ -0,0 +1,41 @@
+use libp2p::core::muxing::StreamMuxerBox;
+use libp2p::core::transport::Boxed;
+use libp2p::core::upgrade;
+use libp2p::identity::{Keypair, secp256k1};
+use libp2p::pnet::{PnetConfig, PreSharedKey};
+use libp2p::{PeerId, Transport, identity, noise, tcp, yamux};
+use std::fs;
+use std::str::FromStr;
+
+pub fn peer_id(address: &str) -> Result<PeerId, std::io::Error> {
+    let bytes = hex::decode(address)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let public = secp256k1::PublicKey::try_from_bytes(&bytes)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok(identity::PublicKey::from(public).to_peer_id())
+}
+
+pub fn allowed_peers(addresses: &[String]) -> Result<Vec<PeerId>, std::io::Error> {
+    addresses.iter().map(|address| peer_id(address)).collect()
+}
+
+pub fn read_psk(path: &str) -> Result<PreSharedKey, std::io::Error> {
+    let key = fs::read_to_string(path)?;
+    PreSharedKey::from_str(&key)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+// TCP only, QUIC has no way to run the pre-shared key handshake.
+pub fn transport(
+    key: &Keypair,
+    psk: PreSharedKey,
+) -> Result<Boxed<(PeerId, StreamMuxerBox)>, Box<dyn std::error::Error + Send + Sync>> {
+    let noise = noise::Config::new(key)?;
+    Ok(tcp::tokio::Transport::new(tcp::Config::default())
+        .and_then(move |socket, _| PnetConfig::new(psk).handshake(socket))
+        .upgrade(upgrade::Version::V1Lazy)
+        .authenticate(noise)
+        .multiplex(yamux::Config::default())
+        .map(|(peer, muxer), _| (peer, StreamMuxerBox::new(muxer)))
+        .boxed())
+}
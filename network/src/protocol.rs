@@ -0,0 +1,228 @@
+use async_trait::async_trait;
+use chain::snapshot::AccountState;
+use chain::tx::Tx;
+use chain::wire::{
+    self, AccountRequest, BlockRequest, BlockResponse, BlocksRequest, BlocksResponse,
+    LegacyBlockRequest, LegacyBlockResponse, MempoolRequest, MempoolResponse, NonceRequest,
+    NonceResponse, Protocol, SnapshotRequest, SnapshotResponse, Status, TxResponse,
+};
+use futures::prelude::*;
+use libp2p::{StreamProtocol, request_response};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::io;
+use std::marker::PhantomData;
+
+const REQUEST_SIZE_MAXIMUM: u64 = 1024 * 1024;
+const RESPONSE_SIZE_MAXIMUM: u64 = 10 * 1024 * 1024;
+
+pub type Behaviour<Req, Res> = request_response::Behaviour<Codec<Req, Res>>;
+
+// A message encoded for the protocol version negotiated on the stream.
+pub trait Versioned: Sized + Serialize + DeserializeOwned {
+    fn encode(&self, _version: &str) -> io::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    fn decode(_version: &str, data: &[u8]) -> io::Result<Self> {
+        Ok(serde_json::from_slice(data)?)
+    }
+}
+
+impl Versioned for NonceRequest {}
+impl Versioned for NonceResponse {}
+impl Versioned for Tx {}
+impl Versioned for TxResponse {}
+impl Versioned for BlocksRequest {}
+impl Versioned for BlocksResponse {}
+impl Versioned for SnapshotRequest {}
+impl Versioned for SnapshotResponse {}
+impl Versioned for Status {}
+impl Versioned for MempoolRequest {}
+impl Versioned for MempoolResponse {}
+impl Versioned for AccountRequest {}
+impl Versioned for AccountState {}
+
+impl Versioned for BlockRequest {
+    fn encode(&self, version: &str) -> io::Result<Vec<u8>> {
+        if !wire::carries(version, self.since()) {
+            return Err(unsupported(version));
+        }
+        match self {
+            BlockRequest::ByIdx { idx } if version == "0.0.1" => {
+                Ok(serde_json::to_vec(&LegacyBlockRequest { idx: *idx })?)
+            }
+            _ => Ok(serde_json::to_vec(self)?),
+        }
+    }
+
+    fn decode(version: &str, data: &[u8]) -> io::Result<Self> {
+        if version == "0.0.1" {
+            let request: LegacyBlockRequest = serde_json::from_slice(data)?;
+            return Ok(BlockRequest::ByIdx { idx: request.idx });
+        }
+        let request: BlockRequest = serde_json::from_slice(data)?;
+        if !wire::carries(version, request.since()) {
+            return Err(unsupported(version));
+        }
+        Ok(request)
+    }
+}
+
+impl Versioned for BlockResponse {
+    fn encode(&self, version: &str) -> io::Result<Vec<u8>> {
+        if !wire::carries(version, self.since()) {
+            return Err(unsupported(version));
+        }
+        match self {
+            BlockResponse::Block(block) if version == "0.0.1" => {
+                Ok(serde_json::to_vec(&LegacyBlockResponse {
+                    block: block.clone(),
+                })?)
+            }
+            _ => Ok(serde_json::to_vec(self)?),
+        }
+    }
+
+    fn decode(version: &str, data: &[u8]) -> io::Result<Self> {
+        if version == "0.0.1" {
+            let response: LegacyBlockResponse = serde_json::from_slice(data)?;
+            return Ok(BlockResponse::Block(response.block));
+        }
+        let response: BlockResponse = serde_json::from_slice(data)?;
+        if !wire::carries(version, response.since()) {
+            return Err(unsupported(version));
+        }
+        Ok(response)
+    }
+}
+
+fn unsupported(version: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("Message is not supported by /block/{}", version),
+    )
+}
+
+fn version(protocol: &StreamProtocol) -> &str {
+    protocol.as_ref().rsplit('/').next().unwrap_or_default()
+}
+
+pub struct Codec<Req, Res> {
+    phantom: PhantomData<(Req, Res)>,
+}
+
+impl<Req, Res> Default for Codec<Req, Res> {
+    fn default() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<Req, Res> Clone for Codec<Req, Res> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl<Req, Res> request_response::Codec for Codec<Req, Res>
+where
+    Req: Versioned + Send,
+    Res: Versioned + Send,
+{
+    type Protocol = StreamProtocol;
+    type Request = Req;
+    type Response = Res;
+
+    async fn read_request<T>(&mut self, protocol: &StreamProtocol, io: &mut T) -> io::Result<Req>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let mut data = Vec::new();
+        io.take(REQUEST_SIZE_MAXIMUM).read_to_end(&mut data).await?;
+        Req::decode(version(protocol), &data)
+    }
+
+    async fn read_response<T>(&mut self, protocol: &StreamProtocol, io: &mut T) -> io::Result<Res>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        let mut data = Vec::new();
+        io.take(RESPONSE_SIZE_MAXIMUM)
+            .read_to_end(&mut data)
+            .await?;
+        Res::decode(version(protocol), &data)
+    }
+
+    async fn write_request<T>(
+        &mut self,
+        protocol: &StreamProtocol,
+        io: &mut T,
+        request: Req,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        io.write_all(&request.encode(version(protocol))?).await
+    }
+
+    async fn write_response<T>(
+        &mut self,
+        protocol: &StreamProtocol,
+        io: &mut T,
+        response: Res,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        io.write_all(&response.encode(version(protocol))?).await
+    }
+}
+
+pub trait ProtocolExt {
+    fn stream_protocols(&self) -> Vec<StreamProtocol>;
+
+    fn behaviour<Req, Res>(&self) -> Behaviour<Req, Res>
+    where
+        Req: Versioned + Send + Clone + 'static,
+        Res: Versioned + Send + Clone + 'static,
+    {
+        self.behaviour_with_config(request_response::Config::default())
+    }
+
+    fn behaviour_with_config<Req, Res>(
+        &self,
+        config: request_response::Config,
+    ) -> Behaviour<Req, Res>
+    where
+        Req: Versioned + Send + Clone + 'static,
+        Res: Versioned + Send + Clone + 'static;
+}
+
+impl ProtocolExt for Protocol {
+    fn stream_protocols(&self) -> Vec<StreamProtocol> {
+        self.paths()
+            .into_iter()
+            .filter_map(|path| StreamProtocol::try_from_owned(path).ok())
+            .collect()
+    }
+
+    fn behaviour_with_config<Req, Res>(
+        &self,
+        config: request_response::Config,
+    ) -> Behaviour<Req, Res>
+    where
+        Req: Versioned + Send + Clone + 'static,
+        Res: Versioned + Send + Clone + 'static,
+    {
+        Behaviour::with_codec(
+            Codec::default(),
+            self.stream_protocols()
+                .into_iter()
+                .map(|protocol| (protocol, request_response::ProtocolSupport::Full)),
+            config,
+        )
+    }
+}
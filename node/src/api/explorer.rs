@@ -0,0 +1,286 @@
+use crate::blockchain::blockchain::Blockchain;
+use axum::Router;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use chain::block::Block;
+use chain::receipt::Receipt;
+use chain::tx::Tx;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub const DEFAULT_LIMIT: u64 = 20;
+pub const MAX_LIMIT: u64 = 100;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Pagination {
+    #[serde(default)]
+    pub page: u64,
+    #[serde(default = "default_limit")]
+    pub limit: u64,
+}
+
+fn default_limit() -> u64 {
+    DEFAULT_LIMIT
+}
+
+impl Pagination {
+    fn limit(&self) -> u64 {
+        self.limit.clamp(1, MAX_LIMIT)
+    }
+
+    fn offset(&self) -> u64 {
+        self.page.saturating_mul(self.limit())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u64,
+    pub limit: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockSummary {
+    pub idx: u64,
+    pub hash: String,
+    pub parent_hash: String,
+    pub timestamp: u64,
+    pub validator: String,
+    pub tx_count: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BlockDetails {
+    pub block: BlockSummary,
+    pub merkle_root: String,
+    pub signature: String,
+    pub confirmations: u64,
+    pub finalized: bool,
+    pub txs: Option<Vec<Tx>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TxDetails {
+    pub tx: Tx,
+    pub receipt: Receipt,
+    pub confirmations: u64,
+    pub finalized: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AddressDetails {
+    pub address: String,
+    pub balance: String,
+    pub stake: String,
+    pub nonce: u64,
+    pub txs: Page<Tx>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ValidatorDetails {
+    pub address: String,
+    pub stake: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChainStats {
+    pub height: u64,
+    pub finalized_height: u64,
+    pub tx_count: u64,
+    pub pending_txs: u64,
+    pub total_supply: String,
+    pub total_stake: String,
+    pub validators: u64,
+}
+
+pub struct Explorer {
+    blockchain: Arc<Blockchain>,
+}
+
+impl Explorer {
+    pub fn new(blockchain: Arc<Blockchain>) -> Self {
+        Self { blockchain }
+    }
+
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/api/blocks", get(blocks))
+            .route("/api/blocks/{id}", get(block))
+            .route("/api/txs/{hash}", get(tx))
+            .route("/api/txs/{hash}/proof", get(tx_proof))
+            .route("/api/addresses/{address}", get(address))
+            .route("/api/validators", get(validators))
+            .route("/api/stats", get(stats))
+            .with_state(Arc::new(self))
+    }
+
+    fn summary(&self, block: &Block) -> Result<BlockSummary, std::io::Error> {
+        Ok(BlockSummary {
+            idx: block.idx,
+            hash: block.hash_str(),
+            parent_hash: block.parent_hash.clone(),
+            timestamp: block.timestamp,
+            validator: block.validator.clone(),
+            tx_count: self.blockchain.block_tx_count(block.idx)?,
+        })
+    }
+
+    fn confirmations(&self, idx: u64) -> Result<(u64, bool), std::io::Error> {
+        let head = self.blockchain.find_latest()?.idx;
+        let finalized = idx <= self.blockchain.finalized_height()?;
+        Ok(((head + 1).saturating_sub(idx), finalized))
+    }
+
+    pub fn blocks(&self, pagination: Pagination) -> Result<Page<BlockSummary>, std::io::Error> {
+        let head = self.blockchain.find_latest()?.idx;
+        let mut items = Vec::new();
+        if let Some(start) = head.checked_sub(pagination.offset()) {
+            let end = start.saturating_sub(pagination.limit() - 1);
+            for idx in (end..=start).rev() {
+                if let Some(block) = self.blockchain.find_block_by_idx(idx)? {
+                    items.push(self.summary(&block)?);
+                }
+            }
+        }
+        Ok(Page {
+            items,
+            page: pagination.page,
+            limit: pagination.limit(),
+            total: head + 1,
+        })
+    }
+
+    pub fn block(&self, id: String) -> Result<Option<BlockDetails>, std::io::Error> {
+        let block = match id.parse::<u64>() {
+            Ok(idx) => self.blockchain.find_block_by_idx(idx)?,
+            Err(_) => self.blockchain.find_block_by_hash(id)?,
+        };
+        let Some(block) = block else {
+            return Ok(None);
+        };
+        let (confirmations, finalized) = self.confirmations(block.idx)?;
+        Ok(Some(BlockDetails {
+            block: self.summary(&block)?,
+            merkle_root: block.merkle_root.clone(),
+            signature: block.signature.clone(),
+            confirmations,
+            finalized,
+            txs: block.txs,
+        }))
+    }
+
+    pub fn tx(&self, hash: String) -> Result<Option<TxDetails>, std::io::Error> {
+        let Some(tx) = self.blockchain.find_tx(hash.clone())? else {
+            return Ok(None);
+        };
+        let Some(receipt) = self.blockchain.receipt(hash)? else {
+            return Ok(None);
+        };
+        let (confirmations, finalized) = match receipt.block {
+            Some(idx) => self.confirmations(idx)?,
+            None => (0, false),
+        };
+        Ok(Some(TxDetails {
+            receipt,
+            tx,
+            confirmations,
+            finalized,
+        }))
+    }
+
+    pub fn address(
+        &self,
+        address: String,
+        pagination: Pagination,
+    ) -> Result<AddressDetails, std::io::Error> {
+        let account = self.blockchain.account(address.clone())?;
+        let (items, total) =
+            self.blockchain
+                .wallet_txs(address, pagination.offset(), pagination.limit())?;
+        Ok(AddressDetails {
+            address: account.address,
+            balance: account.balance,
+            stake: account.stake,
+            nonce: account.nonce,
+            txs: Page {
+                items,
+                page: pagination.page,
+                limit: pagination.limit(),
+                total,
+            },
+        })
+    }
+
+    pub fn validators(&self) -> Result<Vec<ValidatorDetails>, std::io::Error> {
+        let mut validators: Vec<ValidatorDetails> = self
+            .blockchain
+            .stakes()?
+            .iter()
+            .map(|stake| ValidatorDetails {
+                address: stake.wallet(),
+                stake: stake.stake().to_string(),
+            })
+            .collect();
+        validators.sort_by(|a, b| a.address.cmp(&b.address));
+        Ok(validators)
+    }
+
+    pub fn stats(&self) -> Result<ChainStats, std::io::Error> {
+        Ok(ChainStats {
+            height: self.blockchain.find_latest()?.idx,
+            finalized_height: self.blockchain.finalized_height()?,
+            tx_count: self.blockchain.tx_count()?,
+            pending_txs: self.blockchain.pending_tx_hashes()?.len() as u64,
+            total_supply: self.blockchain.total_supply()?.to_string(),
+            total_stake: self.blockchain.total_staked()?.to_string(),
+            validators: self.blockchain.stakes()?.len() as u64,
+        })
+    }
+}
+
+fn respond<T: Serialize>(result: Result<Option<T>, std::io::Error>) -> Response {
+    match result {
+        Ok(Some(value)) => Json(value).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "Not found").into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+async fn blocks(
+    State(explorer): State<Arc<Explorer>>,
+    Query(pagination): Query<Pagination>,
+) -> Response {
+    respond(explorer.blocks(pagination).map(Some))
+}
+
+async fn block(State(explorer): State<Arc<Explorer>>, Path(id): Path<String>) -> Response {
+    respond(explorer.block(id))
+}
+
+async fn tx(State(explorer): State<Arc<Explorer>>, Path(hash): Path<String>) -> Response {
+    respond(explorer.tx(hash))
+}
+
+async fn tx_proof(State(explorer): State<Arc<Explorer>>, Path(hash): Path<String>) -> Response {
+    respond(explorer.blockchain.find_tx_proof(hash))
+}
+
+async fn address(
+    State(explorer): State<Arc<Explorer>>,
+    Path(address): Path<String>,
+    Query(pagination): Query<Pagination>,
+) -> Response {
+    respond(explorer.address(address, pagination).map(Some))
+}
+
+async fn validators(State(explorer): State<Arc<Explorer>>) -> Response {
+    respond(explorer.validators().map(Some))
+}
+
+async fn stats(State(explorer): State<Arc<Explorer>>) -> Response {
+    respond(explorer.stats().map(Some))
+}
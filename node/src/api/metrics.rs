@@ -0,0 +1,231 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::hooks::ChainMetrics;
+use axum::Router;
+use axum::extract::State;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::encoding::text::encode;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::{Histogram, exponential_buckets};
+use prometheus_client::registry::Registry;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const PREFIX: &str = "xhcg";
+pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+pub const ROCKSDB_PROPERTIES: [&str; 5] = [
+    "rocksdb.estimate-num-keys",
+    "rocksdb.estimate-live-data-size",
+    "rocksdb.total-sst-files-size",
+    "rocksdb.cur-size-all-mem-tables",
+    "rocksdb.num-running-compactions",
+];
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct ReasonLabels {
+    reason: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct GossipLabels {
+    topic: String,
+    result: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct RequestLabels {
+    protocol: String,
+    outcome: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct PropertyLabels {
+    property: String,
+}
+
+type LatencyFamily = Family<RequestLabels, Histogram, fn() -> Histogram>;
+
+pub struct Metrics {
+    registry: Registry,
+    head_height: Gauge,
+    finalized_height: Gauge,
+    blocks_produced: Counter,
+    blocks_missed: Counter,
+    mempool_size: Gauge,
+    txs_accepted: Counter,
+    txs_rejected: Family<ReasonLabels, Counter>,
+    peers: Gauge,
+    gossip_messages: Family<GossipLabels, Counter>,
+    request_latency: LatencyFamily,
+    sync_target_height: Gauge,
+    rocksdb: Family<PropertyLabels, Gauge>,
+}
+
+fn latency_histogram() -> Histogram {
+    Histogram::new(exponential_buckets(0.005, 2.0, 12))
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let mut metrics = Self {
+            registry: Registry::with_prefix(PREFIX),
+            head_height: Gauge::default(),
+            finalized_height: Gauge::default(),
+            blocks_produced: Counter::default(),
+            blocks_missed: Counter::default(),
+            mempool_size: Gauge::default(),
+            txs_accepted: Counter::default(),
+            txs_rejected: Family::default(),
+            peers: Gauge::default(),
+            gossip_messages: Family::default(),
+            request_latency: Family::new_with_constructor(latency_histogram),
+            sync_target_height: Gauge::default(),
+            rocksdb: Family::default(),
+        };
+        let registry = &mut metrics.registry;
+        registry.register(
+            "head_height",
+            "Height of the chain head",
+            metrics.head_height.clone(),
+        );
+        registry.register(
+            "finalized_height",
+            "Height of the last finalized block",
+            metrics.finalized_height.clone(),
+        );
+        registry.register(
+            "blocks_produced",
+            "Blocks produced by this validator",
+            metrics.blocks_produced.clone(),
+        );
+        registry.register(
+            "blocks_missed",
+            "Slots in which this validator was selected and produced no block",
+            metrics.blocks_missed.clone(),
+        );
+        registry.register(
+            "mempool_size",
+            "Pending transactions",
+            metrics.mempool_size.clone(),
+        );
+        registry.register(
+            "txs_accepted",
+            "Transactions accepted into the mempool",
+            metrics.txs_accepted.clone(),
+        );
+        registry.register(
+            "txs_rejected",
+            "Transactions rejected by reason",
+            metrics.txs_rejected.clone(),
+        );
+        registry.register("peers", "Connected peers", metrics.peers.clone());
+        registry.register(
+            "gossip_messages",
+            "Gossip messages received by topic and validation result",
+            metrics.gossip_messages.clone(),
+        );
+        registry.register(
+            "request_latency_seconds",
+            "Outbound request-response latency by protocol",
+            metrics.request_latency.clone(),
+        );
+        registry.register(
+            "sync_target_height",
+            "Highest head reported by peers",
+            metrics.sync_target_height.clone(),
+        );
+        registry.register("rocksdb", "RocksDB properties", metrics.rocksdb.clone());
+        metrics
+    }
+
+    pub fn peers(&self, count: usize) {
+        self.peers.set(count as i64);
+    }
+
+    pub fn gossip(&self, topic: &str, result: &str) {
+        self.gossip_messages
+            .get_or_create(&GossipLabels {
+                topic: String::from(topic),
+                result: String::from(result),
+            })
+            .inc();
+    }
+
+    pub fn request(&self, protocol: &str, outcome: &str, latency: Duration) {
+        self.request_latency
+            .get_or_create(&RequestLabels {
+                protocol: String::from(protocol),
+                outcome: String::from(outcome),
+            })
+            .observe(latency.as_secs_f64());
+    }
+
+    pub fn sync_target(&self, height: u64) {
+        self.sync_target_height.set(height as i64);
+    }
+
+    pub fn render(&self, blockchain: &Blockchain) -> Result<String, std::io::Error> {
+        self.head_height.set(blockchain.find_latest()?.idx as i64);
+        self.finalized_height
+            .set(blockchain.finalized_height()? as i64);
+        self.mempool_size
+            .set(blockchain.pending_tx_hashes()?.len() as i64);
+        for property in ROCKSDB_PROPERTIES {
+            if let Some(value) = blockchain.db_property(property)? {
+                self.rocksdb
+                    .get_or_create(&PropertyLabels {
+                        property: String::from(property),
+                    })
+                    .set(value as i64);
+            }
+        }
+        let mut text = String::new();
+        encode(&mut text, &self.registry).map_err(std::io::Error::other)?;
+        Ok(text)
+    }
+}
+
+impl ChainMetrics for Metrics {
+    fn tx_accepted(&self) {
+        self.txs_accepted.inc();
+    }
+
+    fn tx_rejected(&self, reason: &str) {
+        self.txs_rejected
+            .get_or_create(&ReasonLabels {
+                reason: String::from(reason),
+            })
+            .inc();
+    }
+
+    fn block_produced(&self) {
+        self.blocks_produced.inc();
+    }
+
+    fn block_missed(&self) {
+        self.blocks_missed.inc();
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn router(blockchain: Arc<Blockchain>, metrics: Arc<Metrics>) -> Router {
+    Router::new()
+        .route("/metrics", get(render))
+        .with_state((blockchain, metrics))
+}
+
+async fn render(State((blockchain, metrics)): State<(Arc<Blockchain>, Arc<Metrics>)>) -> Response {
+    match metrics.render(&blockchain) {
+        Ok(text) => ([(header::CONTENT_TYPE, CONTENT_TYPE)], text).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
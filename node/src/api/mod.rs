@@ -0,0 +1,5 @@
+pub mod explorer;
+pub mod metrics;
+pub mod rpc;
+pub mod server;
+pub mod ws;
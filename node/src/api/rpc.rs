@@ -0,0 +1,240 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::net::mempool;
+use axum::Router;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use chain::tx::Tx;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::io::ErrorKind;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+pub const VERSION: &str = "2.0";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    pub id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+
+    fn internal(e: std::io::Error) -> Self {
+        Self::new(INTERNAL_ERROR, e)
+    }
+}
+
+impl RpcResponse {
+    fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+        Self {
+            jsonrpc: String::from(VERSION),
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+pub struct Rpc {
+    blockchain: Arc<Blockchain>,
+    txs: mpsc::Sender<Tx>,
+}
+
+impl Rpc {
+    pub fn new(blockchain: Arc<Blockchain>, txs: mpsc::Sender<Tx>) -> Self {
+        Self { blockchain, txs }
+    }
+
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/", post(Self::handle_http))
+            .with_state(Arc::new(self))
+    }
+
+    async fn handle_http(State(rpc): State<Arc<Rpc>>, body: String) -> Response {
+        match rpc.handle_json(&body) {
+            Some(response) => axum::Json(response).into_response(),
+            None => StatusCode::NO_CONTENT.into_response(),
+        }
+    }
+
+    // Returns None when the body holds only notifications, requests without an id.
+    pub fn handle_json(&self, body: &str) -> Option<Value> {
+        let value: Value = match serde_json::from_str(body) {
+            Ok(value) => value,
+            Err(e) => {
+                let error = RpcError::new(PARSE_ERROR, e);
+                return Some(json!(RpcResponse::new(Value::Null, Err(error))));
+            }
+        };
+        match value {
+            Value::Array(batch) if !batch.is_empty() => {
+                let responses: Vec<Value> = batch
+                    .into_iter()
+                    .filter_map(|value| self.handle_value(value))
+                    .collect();
+                (!responses.is_empty()).then_some(Value::Array(responses))
+            }
+            value => self.handle_value(value),
+        }
+    }
+
+    fn handle_value(&self, value: Value) -> Option<Value> {
+        let notification = value.as_object().is_some_and(|o| !o.contains_key("id"));
+        match serde_json::from_value::<RpcRequest>(value) {
+            Ok(request) => {
+                let response = self.handle(request);
+                (!notification).then(|| json!(response))
+            }
+            Err(e) => {
+                let error = RpcError::new(INVALID_REQUEST, e);
+                Some(json!(RpcResponse::new(Value::Null, Err(error))))
+            }
+        }
+    }
+
+    pub fn handle(&self, request: RpcRequest) -> RpcResponse {
+        if request.jsonrpc != VERSION {
+            let error = RpcError::new(INVALID_REQUEST, "Unsupported jsonrpc version");
+            return RpcResponse::new(request.id, Err(error));
+        }
+        let result = self.call(&request.method, &request.params);
+        RpcResponse::new(request.id, result)
+    }
+
+    fn call(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        let blockchain = &self.blockchain;
+        match method {
+            "head" => Ok(json!(blockchain.find_latest().map_err(RpcError::internal)?)),
+            "block_by_idx" => {
+                let idx: u64 = param(params, 0)?;
+                Ok(json!(
+                    blockchain
+                        .find_block_by_idx(idx)
+                        .map_err(RpcError::internal)?
+                ))
+            }
+            "block_by_hash" => {
+                let hash: String = param(params, 0)?;
+                Ok(json!(
+                    blockchain
+                        .find_block_by_hash(hash)
+                        .map_err(RpcError::internal)?
+                ))
+            }
+            "tx_by_hash" => {
+                let hash: String = param(params, 0)?;
+                Ok(json!(blockchain.find_tx(hash).map_err(RpcError::internal)?))
+            }
+            "tx_proof" => {
+                let hash: String = param(params, 0)?;
+                Ok(json!(
+                    blockchain.find_tx_proof(hash).map_err(RpcError::internal)?
+                ))
+            }
+            "receipt" => {
+                let hash: String = param(params, 0)?;
+                Ok(json!(blockchain.receipt(hash).map_err(RpcError::internal)?))
+            }
+            "balance" => {
+                let address: String = param(params, 0)?;
+                let balance = blockchain.balance(address).map_err(RpcError::internal)?;
+                Ok(json!(balance.to_string()))
+            }
+            "nonce" => {
+                let address: String = param(params, 0)?;
+                Ok(json!(
+                    blockchain.nonce(address).map_err(RpcError::internal)?
+                ))
+            }
+            "stake" => {
+                let address: String = param(params, 0)?;
+                let stake = blockchain
+                    .wallet_stake(address)
+                    .map_err(RpcError::internal)?
+                    .map(|stake| stake.stake())
+                    .unwrap_or_default();
+                Ok(json!(stake.to_string()))
+            }
+            "validators" => {
+                let stakes = blockchain.stakes().map_err(RpcError::internal)?;
+                let validators: Vec<Value> = stakes
+                    .iter()
+                    .map(|stake| json!({ "address": stake.wallet(), "stake": stake.stake().to_string() }))
+                    .collect();
+                Ok(json!(validators))
+            }
+            "mempool" => {
+                let hashes = mempool::inventory(blockchain).map_err(RpcError::internal)?;
+                Ok(json!(
+                    mempool::find_pending(blockchain, &hashes).map_err(RpcError::internal)?
+                ))
+            }
+            "send_raw_tx" => {
+                let mut tx: Tx = param(params, 0)?;
+                tx.block = None;
+                blockchain.add_tx(&tx).map_err(|e| match e.kind() {
+                    ErrorKind::InvalidInput => RpcError::new(INVALID_PARAMS, e),
+                    _ => RpcError::internal(e),
+                })?;
+                if let Err(e) = self.txs.try_send(tx.clone()) {
+                    warn!("Cannot publish tx {}: {}", tx.hash_str(), e);
+                }
+                Ok(json!(tx.hash_str()))
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method {}", method),
+            )),
+        }
+    }
+}
+
+fn param<T: DeserializeOwned>(params: &Value, idx: usize) -> Result<T, RpcError> {
+    let value = params
+        .get(idx)
+        .cloned()
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Missing param {}", idx)))?;
+    serde_json::from_value(value).map_err(|e| RpcError::new(INVALID_PARAMS, e))
+}
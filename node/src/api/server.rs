@@ -0,0 +1,13 @@
+use axum::Router;
+use tokio::net::TcpListener;
+use tracing::info;
+
+pub async fn bind(address: &str) -> Result<TcpListener, std::io::Error> {
+    let listener = TcpListener::bind(address).await?;
+    info!("API started: http://{}", listener.local_addr()?);
+    Ok(listener)
+}
+
+pub async fn serve(listener: TcpListener, router: Router) -> Result<(), std::io::Error> {
+    axum::serve(listener, router).await
+}
@@ -0,0 +1,184 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::events::ChainEvent;
+use axum::Router;
+use axum::extract::ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::select;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+pub const MAX_REPLAY_BLOCKS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    NewHeads,
+    Finalized,
+    PendingTxs,
+    AddressTxs,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Subscription {
+    pub topic: Topic,
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub from: Option<u64>,
+}
+
+impl Subscription {
+    pub fn matches(&self, event: &ChainEvent) -> bool {
+        match (self.topic, event) {
+            (Topic::NewHeads, ChainEvent::NewHead { .. })
+            | (Topic::Finalized, ChainEvent::Finalized { .. })
+            | (Topic::PendingTxs, ChainEvent::PendingTx { .. }) => true,
+            (Topic::AddressTxs, event) => self
+                .address
+                .as_deref()
+                .is_some_and(|address| event.touches(address)),
+            _ => false,
+        }
+    }
+
+    pub fn head(&self, blockchain: &Blockchain) -> Result<u64, std::io::Error> {
+        match self.topic {
+            Topic::Finalized => blockchain.finalized_height(),
+            _ => Ok(blockchain.find_latest()?.idx),
+        }
+    }
+
+    pub fn exceeds_replay_window(&self, head: u64) -> bool {
+        self.from
+            .is_some_and(|from| from.saturating_add(MAX_REPLAY_BLOCKS) <= head)
+    }
+
+    pub fn replay(
+        &self,
+        blockchain: &Blockchain,
+        idx: u64,
+    ) -> Result<Vec<ChainEvent>, std::io::Error> {
+        let Some(block) = blockchain.find_block_by_idx(idx)? else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Block {} is pruned", idx),
+            ));
+        };
+        let events = match self.topic {
+            Topic::NewHeads => vec![ChainEvent::NewHead { block }],
+            Topic::Finalized => vec![ChainEvent::Finalized { block }],
+            Topic::PendingTxs => Vec::new(),
+            Topic::AddressTxs => block
+                .txs()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|mut tx| {
+                    tx.block = Some(idx);
+                    ChainEvent::IncludedTx { tx }
+                })
+                .filter(|event| self.matches(event))
+                .collect(),
+        };
+        Ok(events)
+    }
+}
+
+pub fn router(blockchain: Arc<Blockchain>) -> Router {
+    Router::new()
+        .route("/ws", get(upgrade))
+        .with_state(blockchain)
+}
+
+async fn upgrade(
+    ws: WebSocketUpgrade,
+    State(blockchain): State<Arc<Blockchain>>,
+    Query(subscription): Query<Subscription>,
+) -> Response {
+    if subscription.topic == Topic::AddressTxs && subscription.address.is_none() {
+        return (StatusCode::BAD_REQUEST, "address is required").into_response();
+    }
+    match subscription.head(&blockchain) {
+        Ok(head) if subscription.exceeds_replay_window(head) => {
+            let message = format!(
+                "from must be within {} blocks of the head",
+                MAX_REPLAY_BLOCKS
+            );
+            return (StatusCode::BAD_REQUEST, message).into_response();
+        }
+        Ok(_) => {}
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+    ws.on_upgrade(move |socket| stream(socket, blockchain, subscription))
+}
+
+async fn send(socket: &mut WebSocket, event: &ChainEvent) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(event).map_err(axum::Error::new)?;
+    socket.send(Message::Text(json.into())).await
+}
+
+async fn replay(
+    socket: &mut WebSocket,
+    blockchain: &Blockchain,
+    subscription: &Subscription,
+    from: u64,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    let head = subscription.head(blockchain)?;
+    for idx in from..=head {
+        for event in subscription.replay(blockchain, idx)? {
+            send(socket, &event).await?;
+        }
+    }
+    Ok(head)
+}
+
+async fn stream(mut socket: WebSocket, blockchain: Arc<Blockchain>, subscription: Subscription) {
+    let mut events = blockchain.subscribe();
+    let mut sent = None;
+    if let Some(from) = subscription.from {
+        match replay(&mut socket, &blockchain, &subscription, from).await {
+            Ok(head) => sent = Some(head),
+            Err(e) => {
+                warn!("Cannot replay blocks from {}: {}", from, e);
+                let close = CloseFrame {
+                    code: close_code::ERROR,
+                    reason: e.to_string().into(),
+                };
+                let _ = socket.send(Message::Close(Some(close))).await;
+                return;
+            }
+        }
+    }
+    loop {
+        select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if sent.is_some_and(|sent| event.height().is_some_and(|idx| idx <= sent)) {
+                        continue;
+                    }
+                    if subscription.matches(&event) && send(&mut socket, &event).await.is_err() {
+                        return;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Subscriber lagged behind by {} events, closing", skipped);
+                    let close = CloseFrame {
+                        code: close_code::AGAIN,
+                        reason: "lagged, resume from the last received height".into(),
+                    };
+                    let _ = socket.send(Message::Close(Some(close))).await;
+                    return;
+                }
+                Err(RecvError::Closed) => return,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                _ => {}
+            },
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::blockchain::blockchain::Blockchain;
+use chain::block::Block;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+// File layout: MAGIC | VERSION | FLAGS | (u32 big-endian length | JSON block)*
+const MAGIC: &[u8; 4] = b"XHCG";
+const VERSION: u8 = 1;
+const FLAG_COMPRESSED: u8 = 1;
+const MAX_BLOCK_SIZE: usize = 16 * 1024 * 1024;
+
+pub fn export(blockchain: &Blockchain, path: &str, compress: bool) -> Result<u64, std::io::Error> {
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(MAGIC)?;
+    let flags = if compress { FLAG_COMPRESSED } else { 0 };
+    file.write_all(&[VERSION, flags])?;
+    let count = if compress {
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        let count = write_blocks(blockchain, &mut encoder)?;
+        encoder.finish()?.flush()?;
+        count
+    } else {
+        let count = write_blocks(blockchain, &mut file)?;
+        file.flush()?;
+        count
+    };
+    Ok(count)
+}
+
+pub fn import(blockchain: &Blockchain, path: &str) -> Result<u64, std::io::Error> {
+    let mut file = BufReader::new(File::open(path)?);
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Not a chain export file",
+        ));
+    }
+    let mut header = [0u8; 2];
+    file.read_exact(&mut header)?;
+    if header[0] != VERSION {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Unsupported export version: {}", header[0]),
+        ));
+    }
+    if header[1] & FLAG_COMPRESSED != 0 {
+        read_blocks(blockchain, &mut GzDecoder::new(file))
+    } else {
+        read_blocks(blockchain, &mut file)
+    }
+}
+
+fn write_blocks<W: Write>(blockchain: &Blockchain, writer: &mut W) -> Result<u64, std::io::Error> {
+    let latest_block = blockchain.find_latest()?;
+    for idx in 0..=latest_block.idx {
+        let Some(block) = blockchain.find_block_by_idx(idx)? else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Block {} not found", idx),
+            ));
+        };
+        let data = serde_json::to_vec(&block)?;
+        writer.write_all(&(data.len() as u32).to_be_bytes())?;
+        writer.write_all(&data)?;
+    }
+    Ok(latest_block.idx + 1)
+}
+
+fn read_blocks<R: Read>(blockchain: &Blockchain, reader: &mut R) -> Result<u64, std::io::Error> {
+    let mut imported = 0;
+    while let Some(block) = read_block(reader)? {
+        let latest_block = blockchain.find_latest()?;
+        if block.idx <= latest_block.idx {
+            let local = blockchain.find_block_by_idx(block.idx)?;
+            if local.map(|local| local.hash_str()) != Some(block.hash_str()) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("Block {} does not match local chain", block.idx),
+                ));
+            }
+            continue;
+        }
+        blockchain.add_block(&block)?;
+        imported += 1;
+    }
+    Ok(imported)
+}
+
+fn read_block<R: Read>(reader: &mut R) -> Result<Option<Block>, std::io::Error> {
+    let mut len = [0u8; 4];
+    let mut read = 0;
+    while read < len.len() {
+        match reader.read(&mut len[read..]) {
+            Ok(0) if read == 0 => return Ok(None),
+            Ok(0) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Truncated block length",
+                ));
+            }
+            Ok(n) => read += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_BLOCK_SIZE {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "Block of {} bytes exceeds the {} bytes limit",
+                len, MAX_BLOCK_SIZE
+            ),
+        ));
+    }
+    let mut data = vec![0u8; len];
+    reader.read_exact(&mut data)?;
+    Ok(Some(serde_json::from_slice(&data)?))
+}
@@ -1,39 +1,137 @@
-use crate::blockchain::block::Block;
-use crate::blockchain::config::Config;
-use crate::blockchain::stake::Stake;
+use crate::blockchain::config::{Config, StorageMode};
+use crate::blockchain::events::{ChainEvent, EVENTS_CAPACITY};
+use crate::blockchain::hooks::{ChainMetrics, NoMetrics};
+use crate::blockchain::integrity::{IntegrityReport, ReindexReport};
+use crate::blockchain::pruning::{History, PruneReport};
+use crate::blockchain::storage::account_storage::AccountStorage;
 use crate::blockchain::storage::block_storage::BlockStorage;
 use crate::blockchain::storage::db;
+use crate::blockchain::storage::db::Access;
 use crate::blockchain::storage::nonce_storage::NonceStorage;
+use crate::blockchain::storage::peer_storage::PeerStorage;
+use crate::blockchain::storage::receipt_storage::ReceiptStorage;
+use crate::blockchain::storage::snapshot_storage::SnapshotStorage;
+use crate::blockchain::storage::state_storage::StateStorage;
+use crate::blockchain::storage::stats_storage::StatsStorage;
 use crate::blockchain::storage::tx_storage::TxStorage;
-use crate::blockchain::system::{STAKE_WALLET, UNSTAKE_WALLET};
-use bigdecimal::num_bigint::{BigInt, ToBigInt};
-use bigdecimal::{BigDecimal, FromPrimitive, Zero};
+use crate::blockchain::storage::validator_storage::ValidatorStorage;
+use bigdecimal::BigDecimal;
+use bigdecimal::num_bigint::BigInt;
+use chain::block::Block;
+use chain::headers::HeaderChain;
+use chain::proof::{AccountProof, AccountTree, TxProof};
+use chain::receipt::Receipt;
+use chain::snapshot::{AccountState, SnapshotManifest, StateChunk};
+use chain::stake::{self, Stake};
+use chain::state::{State, TxRejection};
+use chain::system::GENESIS_WALLET;
 use chain::tx::Tx;
-use sha2::Digest;
+use rocksdb::{DBWithThreadMode, MultiThreaded, WriteBatch};
 use std::collections::HashMap;
 use std::fs;
-use std::hash::{DefaultHasher, Hash, Hasher};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tracing::{Span, debug, info, instrument, warn};
 use wallet::wallet::Wallet;
 
+pub const MAX_ACCOUNT_PROOFS: u64 = 1000;
+const MAX_ACCOUNT_TREES: usize = 2;
+
 pub struct Blockchain {
     wallet: Wallet,
     tx_storage: TxStorage,
     block_storage: BlockStorage,
     nonce_storage: NonceStorage,
+    account_storage: AccountStorage,
+    state_storage: StateStorage,
+    snapshot_storage: SnapshotStorage,
+    peer_storage: PeerStorage,
+    stats_storage: StatsStorage,
+    receipt_storage: ReceiptStorage,
+    validator_storage: ValidatorStorage,
+    snapshot_interval: u64,
+    storage_mode: StorageMode,
+    keep_blocks: u64,
+    finality_depth: u64,
+    events: broadcast::Sender<ChainEvent>,
+    metrics: Arc<dyn ChainMetrics>,
+    account_trees: Mutex<HashMap<String, Arc<AccountTree>>>,
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
 }
 
 impl Blockchain {
     pub fn new(wallet: Wallet, config: &Config) -> Result<Self, std::io::Error> {
-        let db = db::open(config)?;
-        let blockchain = Self {
+        let blockchain = Self::with_db(wallet, config, db::open(config)?);
+        blockchain.load_genesis(config.genesis_path())?;
+        if blockchain.account_storage.is_empty()? {
+            blockchain.rebuild_accounts()?;
+        }
+        Ok(blockchain)
+    }
+
+    pub fn open(config: &Config, access: &Access) -> Result<Self, std::io::Error> {
+        if *access == Access::Primary {
+            return Self::new(Wallet::new(), config);
+        }
+        let blockchain = Self::with_db(Wallet::new(), config, db::open_with(config, access)?);
+        if blockchain.block_storage.find_by_idx(0)?.is_none() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("No chain found in {}", config.storage_path()),
+            ));
+        }
+        Ok(blockchain)
+    }
+
+    pub fn catch_up(&self) -> Result<(), std::io::Error> {
+        self.db
+            .try_catch_up_with_primary()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    fn with_db(wallet: Wallet, config: &Config, db: Arc<DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self {
             wallet,
             tx_storage: TxStorage::new(Arc::clone(&db)),
             nonce_storage: NonceStorage::new(Arc::clone(&db)),
             block_storage: BlockStorage::new(Arc::clone(&db)),
-        };
-        blockchain.load_genesis(config.genesis_path())?;
-        Ok(blockchain)
+            account_storage: AccountStorage::new(Arc::clone(&db)),
+            state_storage: StateStorage::new(Arc::clone(&db)),
+            snapshot_storage: SnapshotStorage::new(Arc::clone(&db)),
+            peer_storage: PeerStorage::new(Arc::clone(&db)),
+            stats_storage: StatsStorage::new(Arc::clone(&db)),
+            receipt_storage: ReceiptStorage::new(Arc::clone(&db)),
+            validator_storage: ValidatorStorage::new(Arc::clone(&db)),
+            snapshot_interval: config.snapshot_interval(),
+            storage_mode: config.storage_mode(),
+            keep_blocks: config.keep_blocks(),
+            finality_depth: config.finality_depth(),
+            events: broadcast::channel(EVENTS_CAPACITY).0,
+            metrics: Arc::new(NoMetrics),
+            account_trees: Mutex::new(HashMap::new()),
+            db,
+        }
+    }
+
+    pub fn peer_storage(&self) -> &PeerStorage {
+        &self.peer_storage
+    }
+
+    pub fn with_metrics(mut self, metrics: Arc<dyn ChainMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    fn write(&self, batch: WriteBatch) -> Result<(), std::io::Error> {
+        self.db
+            .write(batch)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn db_property(&self, name: &str) -> Result<Option<u64>, std::io::Error> {
+        self.db
+            .property_int_value(name)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
     }
 
     fn load_genesis(&self, genesis_path: String) -> Result<(), std::io::Error> {
@@ -41,11 +139,18 @@ impl Blockchain {
             let json = fs::read_to_string(genesis_path)?;
             let txs: Vec<Tx> = serde_json::from_str(&json)?;
             let genesis = Block::genesis(txs.clone());
+            let mut state = State::default();
             for tx in txs {
+                state.apply(&tx);
                 self.tx_storage.save(&tx)?;
                 self.nonce_storage.save(tx.from(), tx.nonce())?
             }
+            for account in state.accounts() {
+                self.account_storage.save(&account)?;
+            }
             self.block_storage.save(&genesis)?;
+            self.index_stats(&genesis)?;
+            self.save_receipts(&genesis)?;
         }
         Ok(())
     }
@@ -59,134 +164,387 @@ impl Blockchain {
     }
 
     pub fn balance(&self, wallet: String) -> Result<BigDecimal, std::io::Error> {
-        let txs = self.tx_storage.find_wallet_txs(wallet.clone())?;
-        let mut balance = BigDecimal::zero();
-        for tx in txs {
-            if tx.to() == wallet || tx.to() == UNSTAKE_WALLET {
-                balance += tx.amount();
-            } else {
-                balance -= tx.amount();
-            }
+        Ok(self.pending_state(&wallet)?.balance(&wallet))
+    }
+
+    fn confirmed_state(&self) -> Result<State, std::io::Error> {
+        State::new(self.account_storage.find_all()?)
+    }
+
+    // Confirmed state of one wallet with its pending txs applied on top.
+    fn pending_state(&self, wallet: &str) -> Result<State, std::io::Error> {
+        let mut state = State::new(self.account_storage.get(wallet)?.into_iter().collect())?;
+        let mut pending: Vec<Tx> = self
+            .tx_storage
+            .find_wallet_txs(wallet.to_string())?
+            .into_iter()
+            .filter(|tx| tx.block.is_none())
+            .collect();
+        pending.sort_by_key(|tx| tx.nonce());
+        for tx in pending {
+            state.apply(&tx);
         }
-        Ok(balance)
+        Ok(state)
     }
 
+    #[instrument(level = "debug", skip_all, fields(hash = %tx.hash_str()))]
     pub fn add_tx(&self, tx: &Tx) -> Result<(), std::io::Error> {
-        let nonce = self.nonce(tx.from())?;
-        if nonce + 1 != tx.nonce() {
+        self.save_pending_tx(tx).inspect_err(|e| {
+            debug!("Tx rejected: {}", e);
+            self.metrics.tx_rejected(Self::rejection_reason(e))
+        })?;
+        self.metrics.tx_accepted();
+        debug!("Tx accepted");
+        self.receipt_storage
+            .save(&Receipt::pending(tx.hash_str()))?;
+        self.publish(ChainEvent::PendingTx { tx: tx.clone() });
+        Ok(())
+    }
+
+    fn rejection_reason(error: &std::io::Error) -> &'static str {
+        error
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<TxRejection>())
+            .map_or("storage", TxRejection::reason)
+    }
+
+    fn save_pending_tx(&self, tx: &Tx) -> Result<(), std::io::Error> {
+        self.pending_state(&tx.from())?.check(tx)?;
+        self.tx_storage.save(tx)?;
+        self.nonce_storage.save(tx.from(), tx.nonce())?;
+        Ok(())
+    }
+
+    pub fn find_tx(&self, hash: String) -> Result<Option<Tx>, std::io::Error> {
+        self.tx_storage.find_by_hash(hash)
+    }
+
+    pub fn receipt(&self, hash: String) -> Result<Option<Receipt>, std::io::Error> {
+        let receipt = match self.receipt_storage.find(&hash)? {
+            Some(receipt) => receipt,
+            None => match self.find_tx(hash.clone())? {
+                Some(Tx {
+                    block: Some(idx), ..
+                }) => {
+                    let index = self
+                        .tx_storage
+                        .find_hashes_by_block_idx(idx.to_string())?
+                        .iter()
+                        .position(|tx| *tx == hash)
+                        .unwrap_or_default();
+                    Receipt::included(hash, idx, index as u64)
+                }
+                Some(_) => Receipt::pending(hash),
+                None => return Ok(None),
+            },
+        };
+        Ok(Some(receipt.finalize(self.finalized_height()?)))
+    }
+
+    pub fn pending_tx_hashes(&self) -> Result<Vec<String>, std::io::Error> {
+        self.tx_storage.find_pending_hashes()
+    }
+
+    // Returns the state after the block, so callers do not have to replay it.
+    pub fn validate_block(&self, block: &Block) -> Result<State, std::io::Error> {
+        let latest_block = self.block_storage.find_latest()?;
+        if block.idx != latest_block.idx + 1 {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
-                format!("Invalid nonce value, expected: {}", nonce + 1),
+                format!("Invalid block index, expected: {}", latest_block.idx + 1),
             ));
         }
-        if !tx.valid() {
+        if block.parent_hash != latest_block.hash_str() {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
-                "Invalid transaction signature",
+                "Invalid parent hash",
             ));
         }
-        if tx.to() == UNSTAKE_WALLET {
-            return if let Some(amount) = tx.amount().to_bigint() {
-                if let Some(stake) = self.wallet_stake(tx.from())
-                    && stake.stake() >= amount
-                {
-                    self.tx_storage.save(tx)?;
-                    self.nonce_storage.save(tx.from(), tx.nonce())?;
-                    return Ok(());
-                }
-                Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidInput,
-                    "Not enough stake",
-                ))
-            } else {
-                Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidInput,
-                    "The value must be an integer",
-                ))
-            };
-        } else {
-            let balance = self.balance(tx.from())?;
-            if balance < tx.amount() {
-                return Err(std::io::Error::new(
+        let txs = block.txs().unwrap_or_default();
+        if block.merkle_root != hex::encode(Block::merkle_root(&txs)) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid merkle root",
+            ));
+        }
+        if !block.valid() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid block signature",
+            ));
+        }
+        let mut state = self.confirmed_state()?;
+        let validator = stake::select_validator(&latest_block.hash_str(), &state.stakes());
+        if validator.as_deref() != Some(block.validator.as_str()) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Block is not signed by the selected validator",
+            ));
+        }
+        for tx in txs {
+            state.apply_checked(&tx).map_err(|e| {
+                std::io::Error::new(
                     std::io::ErrorKind::InvalidInput,
-                    format!("Not enough balance, current: {}", balance),
-                ));
+                    format!("Invalid transaction {}: {}", tx.hash_str(), e),
+                )
+            })?;
+        }
+        if block.state_root != hex::encode(state.root())
+            || block.validators_root != hex::encode(state.validators_root())
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid state root",
+            ));
+        }
+        Ok(state)
+    }
+
+    // Light clients and fast syncing nodes fetch the set whenever a header commits a new one.
+    fn save_validators(&self, block: &Block, state: &State) -> Result<(), std::io::Error> {
+        let parent = self.block_storage.find_by_idx(block.idx - 1)?;
+        if parent.is_none_or(|parent| parent.validators_root != block.validators_root) {
+            self.validator_storage.save(block.idx, &state.stakes())?;
+        }
+        Ok(())
+    }
+
+    pub fn validators(&self, idx: u64) -> Result<Option<Vec<Stake>>, std::io::Error> {
+        self.validator_storage.find(idx)
+    }
+
+    fn save_accounts(&self, state: &State, txs: &[Tx]) -> Result<(), std::io::Error> {
+        for tx in txs {
+            for address in [tx.from(), tx.to()] {
+                self.account_storage.save(&state.account(&address))?;
             }
-            self.tx_storage.save(tx)?;
-            self.nonce_storage.save(tx.from(), tx.nonce())?;
         }
         Ok(())
     }
 
+    fn rebuild_accounts(&self) -> Result<(), std::io::Error> {
+        let latest_block = self.block_storage.find_latest()?;
+        self.account_storage.clear()?;
+        for account in self.account_states(latest_block.idx)? {
+            self.account_storage.save(&account)?;
+        }
+        Ok(())
+    }
+
+    #[instrument(skip_all, fields(idx = block.idx, hash = %block.hash_str()))]
     pub fn add_block(&self, block: &Block) -> Result<(), std::io::Error> {
-        if let Some(txs) = block.txs() {
-            for tx in txs {
-                self.tx_storage.save(&tx)?;
+        let state = self.validate_block(block)?;
+        let txs = block.txs().unwrap_or_default();
+        for mut tx in txs.clone() {
+            tx.block = Some(block.idx);
+            self.tx_storage.save(&tx)?;
+            if self.nonce(tx.from())? < tx.nonce() {
+                self.nonce_storage.save(tx.from(), tx.nonce())?;
             }
         }
+        self.save_accounts(&state, &txs)?;
+        self.save_validators(block, &state)?;
         self.block_storage.save(block)?;
+        self.index_stats(block)?;
+        self.save_receipts(block)?;
+        self.resolve_pending(block)?;
+        self.snapshot_if_due(block.idx);
+        self.publish_block(block);
+        debug!(
+            "Block added with {} txs",
+            block.txs.as_ref().map_or(0, |txs| txs.len())
+        );
         Ok(())
     }
 
     pub fn find_block_by_idx(&self, idx: u64) -> Result<Option<Block>, std::io::Error> {
-        if let Some(mut block) = self.block_storage.find_by_idx(idx)? {
-            let txs = self.tx_storage.find_by_block_idx(idx)?;
-            block.txs = Some(txs);
-            return Ok(Some(block));
+        let Some(mut block) = self.block_storage.find_by_idx(idx)? else {
+            return Ok(None);
+        };
+        let txs = self.tx_storage.find_by_block_idx(idx)?;
+        // Below the base state a body is only missing if it was actually pruned.
+        if self
+            .state_storage
+            .height()?
+            .is_some_and(|base_height| idx <= base_height)
+            && block.merkle_root != hex::encode(Block::merkle_root(&txs))
+        {
+            return Ok(None);
         }
-        Ok(None)
+        block.txs = Some(txs);
+        Ok(Some(block))
     }
 
-    pub fn wallet_stake(&self, wallet: String) -> Option<Stake> {
-        if let Ok(txs) = self.tx_storage.find_wallet_txs(wallet.clone()) {
-            let mut stake = BigInt::zero();
-            for tx in txs {
-                if tx.to() == STAKE_WALLET {
-                    stake += tx.amount().to_bigint().unwrap();
-                } else if tx.to() == UNSTAKE_WALLET {
-                    stake -= tx.amount().to_bigint().unwrap();
+    pub fn find_block_by_hash(&self, hash: String) -> Result<Option<Block>, std::io::Error> {
+        match self.block_storage.find_by_hash(hash)? {
+            Some(block) => self.find_block_by_idx(block.idx),
+            None => Ok(None),
+        }
+    }
+
+    pub fn find_headers(&self, from: u64, count: u64) -> Result<Vec<Block>, std::io::Error> {
+        let mut headers = Vec::new();
+        for idx in from..from.saturating_add(count) {
+            match self.block_storage.find_by_idx(idx)? {
+                Some(header) => headers.push(header),
+                None => break,
+            }
+        }
+        Ok(headers)
+    }
+
+    pub fn find_blocks(&self, from: u64, count: u64) -> Result<Vec<Block>, std::io::Error> {
+        let mut blocks = Vec::new();
+        for idx in from..from.saturating_add(count) {
+            match self.find_block_by_idx(idx)? {
+                Some(block) => blocks.push(block),
+                None => break,
+            }
+        }
+        Ok(blocks)
+    }
+
+    pub fn find_tx_proof(&self, hash: String) -> Result<Option<TxProof>, std::io::Error> {
+        let Some(tx) = self.tx_storage.find_by_hash(hash)? else {
+            return Ok(None);
+        };
+        let Some(idx) = tx.block else {
+            return Ok(None);
+        };
+        let Some(header) = self.block_storage.find_by_idx(idx)? else {
+            return Ok(None);
+        };
+        let mut leaves = Vec::new();
+        for hash in self.tx_storage.find_hashes_by_block_idx(idx.to_string())? {
+            let leaf = hex::decode(&hash)
+                .ok()
+                .and_then(|leaf| <[u8; 32]>::try_from(leaf).ok())
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("Invalid tx hash: {}", hash),
+                    )
+                })?;
+            leaves.push(leaf);
+        }
+        Ok(TxProof::new(tx, header, &leaves))
+    }
+
+    // Fails with NotFound when the history is pruned, and with InvalidInput when it has more
+    // than MAX_ACCOUNT_PROOFS txs.
+    pub fn find_account_proofs(&self, address: String) -> Result<Vec<TxProof>, std::io::Error> {
+        if let Some(base_height) = self.state_storage.height()? {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("History before block {} is pruned", base_height),
+            ));
+        }
+        if self.tx_storage.count_wallet_txs(address.clone())? > MAX_ACCOUNT_PROOFS {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "History of {} has more than {} txs",
+                    address, MAX_ACCOUNT_PROOFS
+                ),
+            ));
+        }
+        let mut proofs = Vec::new();
+        for tx in self.tx_storage.find_wallet_txs(address)? {
+            if tx.block.is_none() {
+                continue;
+            }
+            match self.find_tx_proof(tx.hash_str())? {
+                Some(proof) => proofs.push(proof),
+                None => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("Block of tx {} is pruned", tx.hash_str()),
+                    ));
                 }
             }
-            return Stake::new(wallet, stake);
         }
-        None
+        Ok(proofs)
     }
 
-    pub fn stakes(&self) -> Result<Vec<Stake>, std::io::Error> {
-        let stakes = self
-            .tx_storage
-            .find_wallet_txs(String::from(STAKE_WALLET))?;
-        let unstakes = self
-            .tx_storage
-            .find_wallet_txs(String::from(UNSTAKE_WALLET))?;
-        let mut stakes_by_wallet: HashMap<String, BigInt> = HashMap::new();
-        for tx in stakes {
-            let stake_by_wallet = stakes_by_wallet.entry(tx.from()).or_insert(BigInt::zero());
-            *stake_by_wallet += tx.amount().to_bigint().unwrap();
-        }
-        for tx in unstakes {
-            let stake_by_wallet = stakes_by_wallet.entry(tx.from()).or_insert(BigInt::zero());
-            *stake_by_wallet -= tx.amount().to_bigint().unwrap();
-        }
-        let mut result = Vec::new();
-        let zero = BigInt::zero();
-        for (wallet, stake) in stakes_by_wallet {
-            if stake != zero {
-                if let Some(stake) = Stake::new(wallet, stake) {
-                    result.push(stake);
+    pub fn find_account_proof(
+        &self,
+        address: String,
+        idx: u64,
+    ) -> Result<Option<AccountProof>, std::io::Error> {
+        let Some(header) = self.block_storage.find_by_idx(idx)? else {
+            return Ok(None);
+        };
+        let tree = self.account_tree(&header)?;
+        Ok(tree.proof(&address, header))
+    }
+
+    // Only the head and the latest snapshot keep their state, other heights would have to be
+    // replayed from the snapshot base. A tree is cached once it matches the header state root.
+    fn account_tree(&self, header: &Block) -> Result<Arc<AccountTree>, std::io::Error> {
+        let hash = header.hash_str();
+        if let Some(tree) = self.account_trees.lock().unwrap().get(&hash) {
+            return Ok(Arc::clone(tree));
+        }
+        let accounts = if header.idx == self.block_storage.find_latest()?.idx {
+            self.account_storage.find_all()?
+        } else if let Some(manifest) = self
+            .snapshot_storage
+            .find_latest()?
+            .filter(|manifest| manifest.height == header.idx)
+        {
+            let mut accounts = Vec::new();
+            for idx in 0..manifest.chunks.len() as u64 {
+                if let Some(chunk) = self.snapshot_storage.find_chunk(manifest.height, idx)? {
+                    accounts.extend(chunk.accounts);
                 }
             }
+            accounts
+        } else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("State of block {} is not kept", header.idx),
+            ));
+        };
+        let tree = Arc::new(AccountTree::new(State::new(accounts)?.accounts()));
+        if tree.root() != header.state_root {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "State of block {} does not match its state root",
+                    header.idx
+                ),
+            ));
         }
-        Ok(result)
+        let mut trees = self.account_trees.lock().unwrap();
+        if trees.len() >= MAX_ACCOUNT_TREES {
+            trees.clear();
+        }
+        trees.insert(hash, Arc::clone(&tree));
+        Ok(tree)
+    }
+
+    pub fn wallet_stake(&self, wallet: String) -> Result<Option<Stake>, std::io::Error> {
+        let state = self.pending_state(&wallet)?;
+        Ok(Stake::new(wallet.clone(), state.stake(&wallet)))
+    }
+
+    pub fn account(&self, address: String) -> Result<AccountState, std::io::Error> {
+        Ok(self.pending_state(&address)?.account(&address))
+    }
+
+    pub fn stakes(&self) -> Result<Vec<Stake>, std::io::Error> {
+        Ok(self.confirmed_state()?.stakes())
     }
 
     pub fn proof_of_stake(&self) -> Result<Block, std::io::Error> {
         let latest_block = self.block_storage.find_latest()?;
-        let stakes = self.stakes()?;
-        let total_stake = Self::total_stake(&stakes);
-        let validator = self.select_validator(latest_block.hash_str(), &stakes, &total_stake);
-        if validator == self.wallet.address() {
-            self.create_block()
+        let validator = stake::select_validator(&latest_block.hash_str(), &self.stakes()?);
+        if validator == Some(self.wallet.address()) {
+            let block = self
+                .create_block()
+                .inspect_err(|_| self.metrics.block_missed())?;
+            self.metrics.block_produced();
+            Ok(block)
         } else {
             Err(std::io::Error::new(
                 std::io::ErrorKind::Other,
@@ -195,60 +553,490 @@ impl Blockchain {
         }
     }
 
-    fn select_validator(
-        &self,
-        block_hash: String,
-        stakes: &Vec<Stake>,
-        total_stake: &BigInt,
-    ) -> String {
-        let stakes_hashes: Vec<[u8; 32]> =
-            stakes.clone().iter().map(|staker| staker.hash()).collect();
-        let merkle_tree =
-            rs_merkle::MerkleTree::<rs_merkle::algorithms::Sha256>::from_leaves(&stakes_hashes);
-        let merkle_root = merkle_tree.root().unwrap();
-
-        let mut hasher = sha2::Sha256::new();
-        hasher.update(hex::decode(block_hash).unwrap().as_slice());
-        hasher.update(merkle_root);
-        let hash: [u8; 32] = hasher.finalize().into();
-        let hash = Self::hash_to_int(hash);
-        let index = BigInt::from_u64(hash).unwrap() % total_stake;
-
-        let mut latest = BigInt::zero();
-        for stake in stakes {
-            if stake.stake() + latest.clone() > index {
-                return stake.wallet();
-            }
-            latest = latest + stake.stake();
-        }
-        String::from("")
-    }
-
+    #[instrument(skip_all, fields(idx, hash))]
     pub fn create_block(&self) -> Result<Block, std::io::Error> {
         let latest_block = self.block_storage.find_latest()?;
-        let pending_txs = self.tx_storage.find_pending()?;
+        let mut state = self.confirmed_state()?;
+        let mut pending_txs = self.tx_storage.find_pending()?;
+        pending_txs.sort_by_key(|tx| tx.nonce());
+        pending_txs.retain(|tx| state.apply_checked(tx).is_ok());
         let block = Block::new(
             &self.wallet,
             latest_block.idx + 1,
             latest_block.hash_str(),
             pending_txs.clone(),
+            &state,
         )?;
+        Span::current()
+            .record("idx", block.idx)
+            .record("hash", block.hash_str());
         self.tx_storage.update_pending(&pending_txs, block.idx)?;
+        self.save_accounts(&state, &pending_txs)?;
+        self.save_validators(&block, &state)?;
         self.block_storage.save(&block)?;
+        self.index_stats(&block)?;
+        self.save_receipts(&block)?;
+        self.resolve_pending(&block)?;
+        self.snapshot_if_due(block.idx);
+        self.publish_block(&block);
+        debug!("Block created with {} txs", pending_txs.len());
         Ok(block)
     }
 
-    fn total_stake(stakes: &Vec<Stake>) -> BigInt {
-        let mut total_stake = BigInt::zero();
-        for stake in stakes {
-            total_stake += stake.stake();
+    fn index_stats(&self, block: &Block) -> Result<(), std::io::Error> {
+        let previous = match block.idx {
+            0 => 0,
+            idx => self.stats_storage.tx_count(idx - 1)?.unwrap_or_default(),
+        };
+        let txs = block.txs.as_ref().map_or(0, |txs| txs.len() as u64);
+        self.stats_storage.save_tx_count(block.idx, previous + txs)
+    }
+
+    fn save_receipts(&self, block: &Block) -> Result<(), std::io::Error> {
+        for (index, tx) in block.txs().unwrap_or_default().iter().enumerate() {
+            let receipt = Receipt::included(tx.hash_str(), block.idx, index as u64);
+            self.receipt_storage.save(&receipt)?;
+        }
+        Ok(())
+    }
+
+    fn resolve_pending(&self, block: &Block) -> Result<(), std::io::Error> {
+        let mut included = HashMap::new();
+        let mut nonces: HashMap<String, u64> = HashMap::new();
+        for tx in block.txs().unwrap_or_default() {
+            let nonce = nonces.entry(tx.from()).or_default();
+            *nonce = (*nonce).max(tx.nonce());
+            included.insert((tx.from(), tx.nonce()), tx.hash_str());
+        }
+        for tx in self.tx_storage.find_pending()? {
+            if nonces
+                .get(&tx.from())
+                .is_none_or(|nonce| tx.nonce() > *nonce)
+            {
+                continue;
+            }
+            let receipt = match included.get(&(tx.from(), tx.nonce())) {
+                Some(by) => Receipt::replaced(tx.hash_str(), by.clone()),
+                None => {
+                    Receipt::dropped(tx.hash_str(), format!("Nonce {} is outdated", tx.nonce()))
+                }
+            };
+            debug!("Pending tx {} {:?}", tx.hash_str(), receipt.status);
+            self.tx_storage.delete_pending(&tx)?;
+            self.receipt_storage.save(&receipt)?;
+        }
+        Ok(())
+    }
+
+    pub fn tx_count(&self) -> Result<u64, std::io::Error> {
+        let latest_block = self.block_storage.find_latest()?;
+        Ok(self
+            .stats_storage
+            .tx_count(latest_block.idx)?
+            .unwrap_or_default())
+    }
+
+    pub fn block_tx_count(&self, idx: u64) -> Result<u64, std::io::Error> {
+        let count = self.stats_storage.tx_count(idx)?.unwrap_or_default();
+        let previous = match idx {
+            0 => 0,
+            idx => self.stats_storage.tx_count(idx - 1)?.unwrap_or(count),
+        };
+        Ok(count.saturating_sub(previous))
+    }
+
+    pub fn wallet_txs(
+        &self,
+        wallet: String,
+        offset: u64,
+        limit: u64,
+    ) -> Result<(Vec<Tx>, u64), std::io::Error> {
+        let total = self.tx_storage.count_wallet_txs(wallet.clone())?;
+        if offset >= total {
+            return Ok((Vec::new(), total));
         }
-        total_stake
+        let txs = self
+            .tx_storage
+            .find_wallet_txs_page(&wallet, offset, limit)?;
+        Ok((txs, total))
+    }
+
+    pub fn total_supply(&self) -> Result<BigDecimal, std::io::Error> {
+        Ok(-self.balance(String::from(GENESIS_WALLET))?)
+    }
+
+    pub fn total_staked(&self) -> Result<BigInt, std::io::Error> {
+        Ok(stake::total_stake(&self.stakes()?))
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ChainEvent> {
+        self.events.subscribe()
     }
 
-    fn hash_to_int(data: [u8; 32]) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        data.hash(&mut hasher);
-        hasher.finish()
+    fn publish(&self, event: ChainEvent) {
+        let _ = self.events.send(event);
+    }
+
+    fn publish_block(&self, block: &Block) {
+        self.publish(ChainEvent::NewHead {
+            block: block.clone(),
+        });
+        for mut tx in block.txs().unwrap_or_default() {
+            tx.block = Some(block.idx);
+            self.publish(ChainEvent::IncludedTx { tx });
+        }
+        if block.idx < self.finality_depth {
+            return;
+        }
+        match self.find_block_by_idx(block.idx - self.finality_depth) {
+            Ok(Some(block)) => self.publish(ChainEvent::Finalized { block }),
+            Ok(None) => {}
+            Err(e) => warn!("Cannot read finalized block: {}", e),
+        }
+    }
+
+    pub fn genesis_hash(&self) -> Result<String, std::io::Error> {
+        match self.block_storage.find_by_idx(0)? {
+            Some(genesis) => Ok(genesis.hash_str()),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Genesis block not found",
+            )),
+        }
+    }
+
+    pub fn finalized_height(&self) -> Result<u64, std::io::Error> {
+        let latest_block = self.block_storage.find_latest()?;
+        Ok(latest_block.idx.saturating_sub(self.finality_depth))
+    }
+
+    pub fn history(&self) -> Result<History, std::io::Error> {
+        Ok(History {
+            mode: self.storage_mode,
+            earliest_block: self.block_storage.find_earliest()?,
+            earliest_body: self.state_storage.height()?.map_or(0, |height| height + 1),
+        })
+    }
+
+    #[instrument(skip_all)]
+    pub fn prune(&self) -> Result<PruneReport, std::io::Error> {
+        let mut report = PruneReport::default();
+        let latest_block = self.block_storage.find_latest()?;
+        let finalized_height = self.finalized_height()?;
+        let horizon = match self.storage_mode {
+            StorageMode::Archive => return Ok(report),
+            StorageMode::Full => latest_block
+                .idx
+                .saturating_sub(self.keep_blocks)
+                .min(finalized_height),
+            StorageMode::Pruned => finalized_height,
+        };
+        let from = self.state_storage.height()?.map_or(0, |height| height + 1);
+        if horizon == 0 || horizon < from {
+            return Ok(report);
+        }
+        // The base state and the deleted bodies must change together, or balances would
+        // count the pruned txs twice.
+        let mut batch = WriteBatch::default();
+        for account in self.account_states(horizon)? {
+            self.state_storage.save(&mut batch, &account)?;
+        }
+        self.state_storage.save_height(&mut batch, horizon)?;
+        report.reclaimed += self
+            .tx_storage
+            .delete_by_block_range(&mut batch, from, horizon)?;
+        report.bodies += horizon - from + 1;
+        if self.storage_mode == StorageMode::Pruned {
+            let earliest = self.block_storage.find_earliest()?;
+            for idx in earliest.max(1)..horizon {
+                report.reclaimed += self.block_storage.delete_by_idx(&mut batch, idx)?;
+                report.headers += 1;
+            }
+            self.block_storage.save_earliest(&mut batch, horizon);
+        }
+        self.write(batch)?;
+        Ok(report)
+    }
+
+    #[instrument(skip_all)]
+    pub fn verify(&self) -> Result<IntegrityReport, std::io::Error> {
+        let mut report = IntegrityReport::default();
+        let latest_block = self.block_storage.find_latest()?;
+        let earliest = self.block_storage.find_earliest()?;
+        let base_height = self.state_storage.height()?;
+        let mut nonces: HashMap<String, u64> = HashMap::new();
+        let mut parent: Option<Block> = None;
+        for idx in std::iter::once(0).chain(earliest.max(1)..=latest_block.idx) {
+            let Some(block) = self.block_storage.find_by_idx(idx)? else {
+                report
+                    .errors
+                    .push(format!("Block {}: header is missing", idx));
+                parent = None;
+                continue;
+            };
+            report.blocks += 1;
+            if block.idx != idx {
+                report
+                    .errors
+                    .push(format!("Block {}: stored under index {}", block.idx, idx));
+            }
+            match self.block_storage.find_by_hash(block.hash_str())? {
+                Some(found) if found.idx == idx => {}
+                _ => report
+                    .errors
+                    .push(format!("Block {}: hash index does not point to block", idx)),
+            }
+            if let Some(parent) = &parent
+                && parent.idx + 1 == idx
+                && block.parent_hash != parent.hash_str()
+            {
+                report.errors.push(format!(
+                    "Block {}: parent hash does not match block {}",
+                    idx, parent.idx
+                ));
+            }
+            parent = Some(block.clone());
+            if base_height.is_some_and(|height| idx <= height) {
+                continue;
+            }
+            let mut txs = Vec::new();
+            for hash in self.tx_storage.find_hashes_by_block_idx(idx.to_string())? {
+                match self.tx_storage.find_by_hash(hash.clone())? {
+                    Some(tx) => txs.push(tx),
+                    None => report
+                        .errors
+                        .push(format!("Block {}: tx {} has no stored body", idx, hash)),
+                }
+            }
+            if block.merkle_root != hex::encode(Block::merkle_root(&txs)) {
+                report.errors.push(format!(
+                    "Block {}: merkle root does not match stored txs",
+                    idx
+                ));
+            }
+            for tx in &txs {
+                let hash = tx.hash_str();
+                if tx.block != Some(idx) {
+                    report.errors.push(format!(
+                        "Block {}: tx {} points to block {:?}",
+                        idx, hash, tx.block
+                    ));
+                }
+                for wallet in [tx.from(), tx.to()] {
+                    if !self.tx_storage.is_wallet_tx(wallet.clone(), &hash)? {
+                        report.errors.push(format!(
+                            "Block {}: tx {} is missing from {} index",
+                            idx, hash, wallet
+                        ));
+                    }
+                }
+                let nonce = nonces.entry(tx.from()).or_default();
+                *nonce = (*nonce).max(tx.nonce());
+            }
+            report.txs += txs.len() as u64;
+        }
+        if self
+            .block_storage
+            .find_by_idx(latest_block.idx + 1)?
+            .is_some()
+        {
+            report.errors.push(format!(
+                "Latest block points to {}, but block {} exists",
+                latest_block.idx,
+                latest_block.idx + 1
+            ));
+        }
+        let mut nonces: Vec<(String, u64)> = nonces.into_iter().collect();
+        nonces.sort();
+        for (wallet, nonce) in nonces {
+            let stored = self.nonce_storage.get(wallet.clone())?;
+            if stored < nonce {
+                report.errors.push(format!(
+                    "Nonce of {} is {}, expected at least {}",
+                    wallet, stored, nonce
+                ));
+            }
+        }
+        Ok(report)
+    }
+
+    #[instrument(skip_all)]
+    pub fn reindex(&self) -> Result<ReindexReport, std::io::Error> {
+        let blocks = self.block_storage.rebuild_indexes()?;
+        let txs = self.tx_storage.rebuild_indexes(&blocks)?;
+        self.reindex_stats(&blocks)?;
+        self.nonce_storage.clear()?;
+        let mut nonces: HashMap<String, u64> = HashMap::new();
+        for account in self.state_storage.find_all()? {
+            nonces.insert(account.address, account.nonce);
+        }
+        for tx in &txs {
+            let nonce = nonces.entry(tx.from()).or_default();
+            *nonce = (*nonce).max(tx.nonce());
+        }
+        for (wallet, nonce) in nonces {
+            if nonce > 0 {
+                self.nonce_storage.save(wallet, nonce)?;
+            }
+        }
+        self.rebuild_accounts()?;
+        Ok(ReindexReport {
+            blocks: blocks.len() as u64,
+            txs: txs.len() as u64,
+        })
+    }
+
+    // Counts of pruned blocks cannot be recomputed, they are kept from the previous index or
+    // seeded from the latest snapshot, so databases created without stats are backfilled too.
+    fn reindex_stats(&self, blocks: &[Block]) -> Result<(), std::io::Error> {
+        let mut previous = HashMap::new();
+        for block in blocks {
+            if let Some(count) = self.stats_storage.tx_count(block.idx)? {
+                previous.insert(block.idx, count);
+            }
+        }
+        if let Some(manifest) = self.snapshot_storage.find_latest()? {
+            previous.entry(manifest.height).or_insert(manifest.tx_count);
+        }
+        self.stats_storage.clear()?;
+        let mut count = 0;
+        for block in blocks {
+            count = match self.find_block_by_idx(block.idx)? {
+                Some(block) => count + block.txs.as_ref().map_or(0, |txs| txs.len() as u64),
+                None => previous.get(&block.idx).copied().unwrap_or(count),
+            };
+            self.stats_storage.save_tx_count(block.idx, count)?;
+        }
+        Ok(())
+    }
+
+    pub fn find_snapshot(&self) -> Result<Option<SnapshotManifest>, std::io::Error> {
+        self.snapshot_storage.find_latest()
+    }
+
+    pub fn find_snapshot_chunk(
+        &self,
+        height: u64,
+        idx: u64,
+    ) -> Result<Option<StateChunk>, std::io::Error> {
+        self.snapshot_storage.find_chunk(height, idx)
+    }
+
+    pub fn create_snapshot(&self, height: u64) -> Result<SnapshotManifest, std::io::Error> {
+        let Some(block) = self.block_storage.find_by_idx(height)? else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("Block {} not found", height),
+            ));
+        };
+        let accounts = if height == self.block_storage.find_latest()?.idx {
+            self.account_storage.find_all()?
+        } else {
+            self.account_states(height)?
+        };
+        let chunks = StateChunk::split(accounts);
+        let tx_count = self.stats_storage.tx_count(height)?.unwrap_or_default();
+        let manifest = SnapshotManifest::new(block, &chunks, tx_count);
+        self.snapshot_storage.save(&manifest, &chunks)?;
+        Ok(manifest)
+    }
+
+    #[instrument(skip_all, fields(height = manifest.height))]
+    pub fn apply_snapshot(
+        &self,
+        manifest: &SnapshotManifest,
+        chunks: &[StateChunk],
+        headers: &HeaderChain,
+    ) -> Result<(), std::io::Error> {
+        if !manifest.valid() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Invalid snapshot manifest",
+            ));
+        }
+        let latest_block = self.block_storage.find_latest()?;
+        if latest_block.idx >= manifest.height {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("Local chain is already at block {}", latest_block.idx),
+            ));
+        }
+        if headers.first().hash_str() != latest_block.hash_str()
+            || headers.head().hash_str() != manifest.block.hash_str()
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Snapshot block does not link to the local chain",
+            ));
+        }
+        if chunks.len() != manifest.chunks.len()
+            || !chunks.iter().all(|chunk| manifest.verify_chunk(chunk))
+            || !manifest.verify_state(chunks)
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "Snapshot chunks do not match state root",
+            ));
+        }
+        let mut batch = WriteBatch::default();
+        self.tx_storage
+            .delete_by_block_range(&mut batch, 0, latest_block.idx)?;
+        for chunk in chunks {
+            for account in &chunk.accounts {
+                self.state_storage.save(&mut batch, account)?;
+            }
+        }
+        self.state_storage
+            .save_height(&mut batch, manifest.height)?;
+        self.write(batch)?;
+        self.account_storage.clear()?;
+        for chunk in chunks {
+            for account in &chunk.accounts {
+                self.account_storage.save(account)?;
+                self.nonce_storage
+                    .save(account.address.clone(), account.nonce)?;
+            }
+        }
+        self.validator_storage
+            .save(manifest.height, headers.stakes())?;
+        for header in &headers.headers()[1..] {
+            self.block_storage.save(header)?;
+        }
+        self.stats_storage
+            .save_tx_count(manifest.height, manifest.tx_count)?;
+        self.snapshot_storage.save(manifest, chunks)?;
+        Ok(())
+    }
+
+    fn snapshot_if_due(&self, idx: u64) {
+        if self.snapshot_interval == 0 || !idx.is_multiple_of(self.snapshot_interval) {
+            return;
+        }
+        match self.create_snapshot(idx) {
+            Ok(manifest) => info!(
+                "Snapshot created at block {}, state root: {}",
+                idx, manifest.state_root
+            ),
+            Err(e) => warn!("Cannot create snapshot: {}", e),
+        }
+    }
+
+    fn account_states(&self, height: u64) -> Result<Vec<AccountState>, std::io::Error> {
+        let (mut state, from) = match self.state_storage.height()? {
+            Some(base_height) if height < base_height => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("State before block {} is pruned", base_height),
+                ));
+            }
+            Some(base_height) => (State::new(self.state_storage.find_all()?)?, base_height + 1),
+            None => (State::default(), 0),
+        };
+        for idx in from..=height {
+            for tx in self.tx_storage.find_by_block_idx(idx)? {
+                state.apply(&tx);
+            }
+        }
+        Ok(state.accounts())
     }
 }
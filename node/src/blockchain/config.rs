@@ -1,7 +1,43 @@
+use chain::headers::DEFAULT_FINALITY_DEPTH;
+use chain::wire::{DEFAULT_REQUEST_RETRIES, DEFAULT_REQUEST_TIMEOUT};
+use client::client::ClientConfig;
 use serde::{Deserialize, Serialize};
 use std::fs;
 
 pub const DEFAULT_CONFIG_PATH: &str = "run/config.json";
+pub const DEFAULT_CHAIN_ID: &str = "xhcg";
+pub const DEFAULT_SNAPSHOT_INTERVAL: u64 = 100;
+pub const DEFAULT_KEEP_BLOCKS: u64 = 1000;
+pub const DEFAULT_BAN_DURATION: u64 = 3600;
+pub const DEFAULT_REQUEST_RATE: u32 = 20;
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageMode {
+    #[default]
+    Archive,
+    Full,
+    Pruned,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Pretty,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Never,
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -11,6 +47,78 @@ pub struct Config {
     storage_path: String,
     genesis_path: String,
     nodes: Vec<String>,
+    #[serde(default = "default_chain_id")]
+    chain_id: String,
+    #[serde(default = "default_snapshot_interval")]
+    snapshot_interval: u64,
+    #[serde(default)]
+    fast_sync: bool,
+    #[serde(default)]
+    storage_mode: StorageMode,
+    #[serde(default = "default_keep_blocks")]
+    keep_blocks: u64,
+    #[serde(default = "default_finality_depth")]
+    finality_depth: u64,
+    #[serde(default = "default_ban_duration")]
+    ban_duration: u64,
+    #[serde(default = "default_request_rate")]
+    request_rate: u32,
+    #[serde(default = "default_request_timeout")]
+    request_timeout: u64,
+    #[serde(default = "default_request_retries")]
+    request_retries: u32,
+    #[serde(default)]
+    psk_path: Option<String>,
+    #[serde(default)]
+    allowed_validators: Vec<String>,
+    #[serde(default)]
+    rpc_address: Option<String>,
+    #[serde(default)]
+    metrics_address: Option<String>,
+    #[serde(default = "default_log_level")]
+    log_level: String,
+    #[serde(default)]
+    log_format: LogFormat,
+    #[serde(default)]
+    log_file: Option<String>,
+    #[serde(default)]
+    log_rotation: LogRotation,
+}
+
+fn default_chain_id() -> String {
+    String::from(DEFAULT_CHAIN_ID)
+}
+
+fn default_snapshot_interval() -> u64 {
+    DEFAULT_SNAPSHOT_INTERVAL
+}
+
+fn default_keep_blocks() -> u64 {
+    DEFAULT_KEEP_BLOCKS
+}
+
+fn default_finality_depth() -> u64 {
+    DEFAULT_FINALITY_DEPTH
+}
+
+fn default_ban_duration() -> u64 {
+    DEFAULT_BAN_DURATION
+}
+
+fn default_request_rate() -> u32 {
+    DEFAULT_REQUEST_RATE
+}
+
+fn default_request_timeout() -> u64 {
+    DEFAULT_REQUEST_TIMEOUT
+}
+
+fn default_request_retries() -> u32 {
+    DEFAULT_REQUEST_RETRIES
+}
+
+fn default_log_level() -> String {
+    String::from(DEFAULT_LOG_LEVEL)
 }
 
 impl Config {
@@ -29,6 +137,24 @@ impl Config {
             storage_path,
             genesis_path,
             nodes,
+            chain_id: String::from(DEFAULT_CHAIN_ID),
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            fast_sync: false,
+            storage_mode: StorageMode::Archive,
+            keep_blocks: DEFAULT_KEEP_BLOCKS,
+            finality_depth: DEFAULT_FINALITY_DEPTH,
+            ban_duration: DEFAULT_BAN_DURATION,
+            request_rate: DEFAULT_REQUEST_RATE,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            request_retries: DEFAULT_REQUEST_RETRIES,
+            psk_path: None,
+            allowed_validators: Vec::new(),
+            rpc_address: None,
+            metrics_address: None,
+            log_level: String::from(DEFAULT_LOG_LEVEL),
+            log_format: LogFormat::Pretty,
+            log_file: None,
+            log_rotation: LogRotation::Daily,
         }
     }
 
@@ -62,4 +188,86 @@ impl Config {
     pub fn nodes(&self) -> Vec<String> {
         self.nodes.clone()
     }
+
+    pub fn chain_id(&self) -> String {
+        self.chain_id.clone()
+    }
+
+    pub fn snapshot_interval(&self) -> u64 {
+        self.snapshot_interval
+    }
+
+    pub fn fast_sync(&self) -> bool {
+        self.fast_sync
+    }
+
+    pub fn storage_mode(&self) -> StorageMode {
+        self.storage_mode
+    }
+
+    pub fn keep_blocks(&self) -> u64 {
+        self.keep_blocks
+    }
+
+    pub fn finality_depth(&self) -> u64 {
+        self.finality_depth
+    }
+
+    pub fn ban_duration(&self) -> u64 {
+        self.ban_duration
+    }
+
+    pub fn request_rate(&self) -> u32 {
+        self.request_rate
+    }
+
+    pub fn request_timeout(&self) -> u64 {
+        self.request_timeout
+    }
+
+    pub fn request_retries(&self) -> u32 {
+        self.request_retries
+    }
+
+    pub fn client_config(&self) -> ClientConfig {
+        ClientConfig {
+            nodes: self.nodes(),
+            request_timeout: self.request_timeout(),
+            request_retries: self.request_retries(),
+            psk_path: self.psk_path(),
+            allowed_validators: self.allowed_validators(),
+        }
+    }
+
+    pub fn psk_path(&self) -> Option<String> {
+        self.psk_path.clone()
+    }
+
+    pub fn allowed_validators(&self) -> Vec<String> {
+        self.allowed_validators.clone()
+    }
+
+    pub fn rpc_address(&self) -> Option<String> {
+        self.rpc_address.clone()
+    }
+
+    pub fn metrics_address(&self) -> Option<String> {
+        self.metrics_address.clone()
+    }
+
+    pub fn log_level(&self) -> String {
+        self.log_level.clone()
+    }
+
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format
+    }
+
+    pub fn log_file(&self) -> Option<String> {
+        self.log_file.clone()
+    }
+
+    pub fn log_rotation(&self) -> LogRotation {
+        self.log_rotation
+    }
 }
@@ -0,0 +1,33 @@
+use chain::block::Block;
+use chain::tx::Tx;
+use serde::{Deserialize, Serialize};
+
+pub const EVENTS_CAPACITY: usize = 4096;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ChainEvent {
+    NewHead { block: Block },
+    Finalized { block: Block },
+    PendingTx { tx: Tx },
+    IncludedTx { tx: Tx },
+}
+
+impl ChainEvent {
+    pub fn height(&self) -> Option<u64> {
+        match self {
+            ChainEvent::NewHead { block } | ChainEvent::Finalized { block } => Some(block.idx),
+            ChainEvent::IncludedTx { tx } => tx.block,
+            ChainEvent::PendingTx { .. } => None,
+        }
+    }
+
+    pub fn touches(&self, address: &str) -> bool {
+        match self {
+            ChainEvent::PendingTx { tx } | ChainEvent::IncludedTx { tx } => {
+                tx.from() == address || tx.to() == address
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+pub trait ChainMetrics: Send + Sync {
+    fn tx_accepted(&self) {}
+
+    fn tx_rejected(&self, _reason: &str) {}
+
+    fn block_produced(&self) {}
+
+    fn block_missed(&self) {}
+}
+
+pub struct NoMetrics;
+
+impl ChainMetrics for NoMetrics {}
@@ -0,0 +1,12 @@
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub blocks: u64,
+    pub txs: u64,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReindexReport {
+    pub blocks: u64,
+    pub txs: u64,
+}
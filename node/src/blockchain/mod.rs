@@ -1,6 +1,8 @@
-pub mod block;
+pub mod archive;
 pub mod blockchain;
 pub mod config;
-pub mod stake;
+pub mod events;
+pub mod hooks;
+pub mod integrity;
+pub mod pruning;
 pub mod storage;
-mod system;
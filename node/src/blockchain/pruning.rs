@@ -0,0 +1,16 @@
+use crate::blockchain::config::StorageMode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    pub mode: StorageMode,
+    pub earliest_block: u64,
+    pub earliest_body: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    pub bodies: u64,
+    pub headers: u64,
+    pub reclaimed: u64,
+}
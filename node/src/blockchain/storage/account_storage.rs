@@ -0,0 +1,61 @@
+use crate::blockchain::storage::db;
+use chain::snapshot::AccountState;
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use std::sync::Arc;
+
+const ACCOUNT_PREFIX: &str = "account.";
+
+pub struct AccountStorage {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+}
+
+impl AccountStorage {
+    pub fn new(db: Arc<DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self { db }
+    }
+
+    pub fn save(&self, account: &AccountState) -> Result<(), std::io::Error> {
+        let data = serde_json::to_vec(account)?;
+        self.db
+            .put(self.build_key(&account.address), data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(())
+    }
+
+    pub fn get(&self, address: &str) -> Result<Option<AccountState>, std::io::Error> {
+        if let Some(data) = self
+            .db
+            .get(self.build_key(address))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        {
+            Ok(Some(serde_json::from_slice(&data)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn find_all(&self) -> Result<Vec<AccountState>, std::io::Error> {
+        let mut accounts = Vec::new();
+        for (_, value) in db::find_by_prefix(&self.db, ACCOUNT_PREFIX)? {
+            accounts.push(serde_json::from_slice(&value)?);
+        }
+        Ok(accounts)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, std::io::Error> {
+        Ok(db::find_by_prefix(&self.db, ACCOUNT_PREFIX)?.is_empty())
+    }
+
+    pub fn clear(&self) -> Result<(), std::io::Error> {
+        for (key, _) in db::find_by_prefix(&self.db, ACCOUNT_PREFIX)? {
+            self.db
+                .delete(key)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(())
+    }
+
+    fn build_key(&self, address: &str) -> String {
+        format!("{}{}", ACCOUNT_PREFIX, address)
+    }
+}
@@ -1,7 +1,9 @@
-use crate::blockchain::block::Block;
-use rocksdb::{DBWithThreadMode, MultiThreaded};
+use crate::blockchain::storage::db;
+use chain::block::Block;
+use rocksdb::{DBWithThreadMode, MultiThreaded, WriteBatch};
 use std::str::FromStr;
 use std::sync::Arc;
+use tracing::instrument;
 
 pub struct BlockStorage {
     db: Arc<DBWithThreadMode<MultiThreaded>>,
@@ -12,6 +14,7 @@ impl BlockStorage {
         Self { db }
     }
 
+    #[instrument(level = "trace", skip_all, fields(idx = block.idx))]
     pub fn save(&self, block: &Block) -> Result<(), std::io::Error> {
         let mut block = block.clone();
         block.txs = None;
@@ -74,6 +77,79 @@ impl BlockStorage {
         ))
     }
 
+    pub fn delete_by_idx(&self, batch: &mut WriteBatch, idx: u64) -> Result<u64, std::io::Error> {
+        let key = self.build_key(&idx.to_string());
+        let Some(json) = self
+            .db
+            .get(&key)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        else {
+            return Ok(0);
+        };
+        let block: Block = serde_json::from_slice(&json)?;
+        let hash_key = self.build_key(&block.hash_str());
+        batch.delete(&key);
+        batch.delete(&hash_key);
+        Ok((key.len() + json.len() + hash_key.len() + idx.to_string().len()) as u64)
+    }
+
+    pub fn find_earliest(&self) -> Result<u64, std::io::Error> {
+        if let Some(idx) = self
+            .db
+            .get("block.earliest")
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        {
+            return Ok(serde_json::from_slice(&idx)?);
+        }
+        Ok(0)
+    }
+
+    pub fn save_earliest(&self, batch: &mut WriteBatch, idx: u64) {
+        batch.put("block.earliest", idx.to_string());
+    }
+
+    pub fn rebuild_indexes(&self) -> Result<Vec<Block>, std::io::Error> {
+        let mut blocks = Vec::new();
+        let mut indexes = Vec::new();
+        let mut corrupted = Vec::new();
+        for (key, value) in db::find_by_prefix(&self.db, &self.build_key(""))? {
+            if key == "block.earliest" {
+                continue;
+            }
+            let is_block = key[self.build_key("").len()..]
+                .chars()
+                .all(|c| c.is_ascii_digit());
+            match serde_json::from_slice::<Block>(&value) {
+                Ok(block) if is_block => blocks.push(block),
+                _ if is_block => corrupted.push(key),
+                _ => indexes.push(key),
+            }
+        }
+        if !corrupted.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Cannot parse {}", corrupted.join(", ")),
+            ));
+        }
+        for key in indexes {
+            self.db
+                .delete(key)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        blocks.sort_by_key(|block| block.idx);
+        for block in &blocks {
+            self.db
+                .put(self.build_key(&block.hash_str()), block.idx.to_string())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        if let Some(latest) = blocks.last() {
+            self.db
+                .put("block.latest", latest.idx.to_string())
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(blocks)
+    }
+
     fn build_key(&self, value: &str) -> String {
         format!("block.{}", value)
     }
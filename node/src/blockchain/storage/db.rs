@@ -1,11 +1,74 @@
-use std::sync::Arc;
 use crate::blockchain::config;
-use rocksdb::{DBWithThreadMode, MultiThreaded, Options};
+use rocksdb::{DBWithThreadMode, Direction, IteratorMode, MultiThreaded, Options};
+use std::sync::Arc;
+use tracing::instrument;
 
-pub fn open(config: &config::Config) -> Result<Arc<DBWithThreadMode<MultiThreaded>>, std::io::Error> {
+#[instrument(skip_all, fields(path = config.storage_path()), err)]
+pub fn open(
+    config: &config::Config,
+) -> Result<Arc<DBWithThreadMode<MultiThreaded>>, std::io::Error> {
     let mut options = Options::default();
     options.create_if_missing(true);
     let db = DBWithThreadMode::open(&options, config.storage_path())
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
     Ok(Arc::new(db))
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Access {
+    Primary,
+    ReadOnly,
+    Secondary(String),
+}
+
+pub fn open_with(
+    config: &config::Config,
+    access: &Access,
+) -> Result<Arc<DBWithThreadMode<MultiThreaded>>, std::io::Error> {
+    match access {
+        Access::Primary => open(config),
+        Access::ReadOnly => open_read_only(config),
+        Access::Secondary(secondary_path) => open_secondary(config, secondary_path),
+    }
+}
+
+#[instrument(skip_all, fields(path = config.storage_path()), err)]
+pub fn open_read_only(
+    config: &config::Config,
+) -> Result<Arc<DBWithThreadMode<MultiThreaded>>, std::io::Error> {
+    let db =
+        DBWithThreadMode::open_for_read_only(&Options::default(), config.storage_path(), false)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok(Arc::new(db))
+}
+
+#[instrument(skip_all, fields(path = config.storage_path(), secondary_path = secondary_path), err)]
+pub fn open_secondary(
+    config: &config::Config,
+    secondary_path: &str,
+) -> Result<Arc<DBWithThreadMode<MultiThreaded>>, std::io::Error> {
+    let mut options = Options::default();
+    options.set_max_open_files(-1);
+    let primary_path = config.storage_path();
+    let db = DBWithThreadMode::open_as_secondary(&options, primary_path.as_str(), secondary_path)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok(Arc::new(db))
+}
+
+pub fn find_by_prefix(
+    db: &DBWithThreadMode<MultiThreaded>,
+    prefix: &str,
+) -> Result<Vec<(String, Vec<u8>)>, std::io::Error> {
+    let mut result = Vec::new();
+    for item in db.iterator(IteratorMode::From(prefix.as_bytes(), Direction::Forward)) {
+        let (key, value) =
+            item.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if !key.starts_with(prefix.as_bytes()) {
+            break;
+        }
+        let key = String::from_utf8(key.to_vec())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        result.push((key, value.to_vec()));
+    }
+    Ok(result)
+}
@@ -1,4 +1,11 @@
+pub mod account_storage;
+pub mod block_storage;
 pub mod db;
 pub mod nonce_storage;
+pub mod peer_storage;
+pub mod receipt_storage;
+pub mod snapshot_storage;
+pub mod state_storage;
+pub mod stats_storage;
 pub mod tx_storage;
-pub mod block_storage;
+pub mod validator_storage;
@@ -1,3 +1,4 @@
+use crate::blockchain::storage::db;
 use rocksdb::{DBWithThreadMode, MultiThreaded};
 use std::sync::Arc;
 
@@ -32,6 +33,15 @@ impl NonceStorage {
         }
     }
 
+    pub fn clear(&self) -> Result<(), std::io::Error> {
+        for (key, _) in db::find_by_prefix(&self.db, &self.build_key(""))? {
+            self.db
+                .delete(key)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(())
+    }
+
     fn build_key(&self, value: &str) -> String {
         format!("nonce.{}", value)
     }
@@ -0,0 +1,44 @@
+use crate::blockchain::storage::db;
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use std::sync::Arc;
+
+const PEER_PREFIX: &str = "peer.";
+const MAX_ADDRESSES: usize = 8;
+
+pub struct PeerStorage {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+}
+
+impl PeerStorage {
+    pub fn new(db: Arc<DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self { db }
+    }
+
+    pub fn save(&self, peer_id: &str, addresses: &[String]) -> Result<(), std::io::Error> {
+        let addresses: Vec<&String> = addresses.iter().take(MAX_ADDRESSES).collect();
+        self.db
+            .put(self.build_key(peer_id), serde_json::to_vec(&addresses)?)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(())
+    }
+
+    pub fn find_all(&self) -> Result<Vec<(String, Vec<String>)>, std::io::Error> {
+        let mut peers = Vec::new();
+        for (key, value) in db::find_by_prefix(&self.db, PEER_PREFIX)? {
+            let peer_id = key[PEER_PREFIX.len()..].to_string();
+            peers.push((peer_id, serde_json::from_slice(&value)?));
+        }
+        Ok(peers)
+    }
+
+    pub fn delete(&self, peer_id: &str) -> Result<(), std::io::Error> {
+        self.db
+            .delete(self.build_key(peer_id))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(())
+    }
+
+    fn build_key(&self, peer_id: &str) -> String {
+        format!("{}{}", PEER_PREFIX, peer_id)
+    }
+}
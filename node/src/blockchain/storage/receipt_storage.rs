@@ -0,0 +1,35 @@
+use chain::receipt::Receipt;
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use std::sync::Arc;
+
+pub struct ReceiptStorage {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+}
+
+impl ReceiptStorage {
+    pub fn new(db: Arc<DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self { db }
+    }
+
+    pub fn save(&self, receipt: &Receipt) -> Result<(), std::io::Error> {
+        let data = serde_json::to_vec(receipt)?;
+        self.db
+            .put(self.build_key(&receipt.hash), data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn find(&self, hash: &str) -> Result<Option<Receipt>, std::io::Error> {
+        match self
+            .db
+            .get(self.build_key(hash))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn build_key(&self, hash: &str) -> String {
+        format!("receipt.{}", hash)
+    }
+}
@@ -0,0 +1,99 @@
+use chain::snapshot::{SnapshotManifest, StateChunk};
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use std::sync::Arc;
+
+const LATEST_KEY: &str = "snapshot.latest";
+
+pub struct SnapshotStorage {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+}
+
+impl SnapshotStorage {
+    pub fn new(db: Arc<DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self { db }
+    }
+
+    pub fn save(
+        &self,
+        manifest: &SnapshotManifest,
+        chunks: &[StateChunk],
+    ) -> Result<(), std::io::Error> {
+        let previous = self.find_latest()?;
+        for chunk in chunks {
+            let key = self.build_key(&format!("{}.{}", manifest.height, chunk.idx));
+            self.db
+                .put(key, serde_json::to_vec(chunk)?)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        self.db
+            .put(
+                self.build_key(&manifest.height.to_string()),
+                serde_json::to_vec(manifest)?,
+            )
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        self.db
+            .put(LATEST_KEY, manifest.height.to_string())
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if let Some(previous) = previous
+            && previous.height != manifest.height
+        {
+            self.delete(&previous)?;
+        }
+        Ok(())
+    }
+
+    pub fn find_latest(&self) -> Result<Option<SnapshotManifest>, std::io::Error> {
+        if let Some(height) = self
+            .db
+            .get(LATEST_KEY)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        {
+            let height = String::from_utf8(height)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            return self.find_by_height(&height);
+        }
+        Ok(None)
+    }
+
+    pub fn find_chunk(&self, height: u64, idx: u64) -> Result<Option<StateChunk>, std::io::Error> {
+        let key = self.build_key(&format!("{}.{}", height, idx));
+        if let Some(data) = self
+            .db
+            .get(key)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        {
+            Ok(Some(serde_json::from_slice(&data)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn find_by_height(&self, height: &str) -> Result<Option<SnapshotManifest>, std::io::Error> {
+        if let Some(data) = self
+            .db
+            .get(self.build_key(height))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        {
+            Ok(Some(serde_json::from_slice(&data)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn delete(&self, manifest: &SnapshotManifest) -> Result<(), std::io::Error> {
+        for idx in 0..manifest.chunks.len() {
+            let key = self.build_key(&format!("{}.{}", manifest.height, idx));
+            self.db
+                .delete(key)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        self.db
+            .delete(self.build_key(&manifest.height.to_string()))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(())
+    }
+
+    fn build_key(&self, value: &str) -> String {
+        format!("snapshot.{}", value)
+    }
+}
@@ -0,0 +1,58 @@
+use crate::blockchain::storage::db;
+use chain::snapshot::AccountState;
+use rocksdb::{DBWithThreadMode, MultiThreaded, WriteBatch};
+use std::sync::Arc;
+
+const HEIGHT_KEY: &str = "state.height";
+const ACCOUNT_PREFIX: &str = "state.account.";
+
+pub struct StateStorage {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+}
+
+impl StateStorage {
+    pub fn new(db: Arc<DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self { db }
+    }
+
+    pub fn save(
+        &self,
+        batch: &mut WriteBatch,
+        account: &AccountState,
+    ) -> Result<(), std::io::Error> {
+        batch.put(
+            self.build_key(&account.address),
+            serde_json::to_vec(account)?,
+        );
+        Ok(())
+    }
+
+    pub fn find_all(&self) -> Result<Vec<AccountState>, std::io::Error> {
+        let mut accounts = Vec::new();
+        for (_, value) in db::find_by_prefix(&self.db, ACCOUNT_PREFIX)? {
+            accounts.push(serde_json::from_slice(&value)?);
+        }
+        Ok(accounts)
+    }
+
+    pub fn height(&self) -> Result<Option<u64>, std::io::Error> {
+        if let Some(data) = self
+            .db
+            .get(HEIGHT_KEY)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        {
+            Ok(Some(serde_json::from_slice(&data)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn save_height(&self, batch: &mut WriteBatch, height: u64) -> Result<(), std::io::Error> {
+        batch.put(HEIGHT_KEY, serde_json::to_vec(&height)?);
+        Ok(())
+    }
+
+    fn build_key(&self, address: &str) -> String {
+        format!("{}{}", ACCOUNT_PREFIX, address)
+    }
+}
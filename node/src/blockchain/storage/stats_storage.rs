@@ -0,0 +1,44 @@
+use crate::blockchain::storage::db;
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use std::sync::Arc;
+
+pub struct StatsStorage {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+}
+
+impl StatsStorage {
+    pub fn new(db: Arc<DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self { db }
+    }
+
+    pub fn save_tx_count(&self, idx: u64, count: u64) -> Result<(), std::io::Error> {
+        let data = serde_json::to_vec(&count)?;
+        self.db
+            .put(self.build_key(idx), data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn tx_count(&self, idx: u64) -> Result<Option<u64>, std::io::Error> {
+        match self
+            .db
+            .get(self.build_key(idx))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn clear(&self) -> Result<(), std::io::Error> {
+        for (key, _) in db::find_by_prefix(&self.db, "stats.")? {
+            self.db
+                .delete(key)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(())
+    }
+
+    fn build_key(&self, idx: u64) -> String {
+        format!("stats.{}", idx)
+    }
+}
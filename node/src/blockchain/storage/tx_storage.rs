@@ -1,9 +1,13 @@
+use crate::blockchain::storage::db;
+use chain::block::Block;
 use chain::tx::Tx;
-use rocksdb::{DBWithThreadMode, MultiThreaded};
-use std::collections::{BTreeSet, HashSet};
+use rocksdb::{DBWithThreadMode, Direction, IteratorMode, MultiThreaded, ReadOptions, WriteBatch};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
+use tracing::instrument;
 
 const NO_BLOCK_IDX: &str = "empty";
+const WALLET_ORDER_PREFIX: &str = "wallet_tx.";
 
 pub struct TxStorage {
     db: Arc<DBWithThreadMode<MultiThreaded>>,
@@ -14,22 +18,46 @@ impl TxStorage {
         Self { db }
     }
 
+    #[instrument(level = "trace", skip_all, fields(hash = %tx.hash_str()))]
     pub fn save(&self, tx: &Tx) -> Result<(), std::io::Error> {
         self.save_without_idx(tx)?;
-        self.add_to_txs_index(tx.from(), tx.hash_str())?;
-        self.add_to_txs_index(tx.to(), tx.hash_str())?;
+        self.add_to_txs_index(tx.from(), tx)?;
+        self.add_to_txs_index(tx.to(), tx)?;
         self.add_to_block_idx(tx)?;
         Ok(())
     }
 
-    fn add_to_txs_index(&self, wallet: String, tx_hash: String) -> Result<(), std::io::Error> {
+    fn add_to_txs_index(&self, wallet: String, tx: &Tx) -> Result<(), std::io::Error> {
         let mut txs = self.find_wallet_txs_hashes(wallet.clone())?;
-        txs.insert(tx_hash);
+        txs.insert(tx.hash_str());
         let data = serde_json::to_vec(&txs)?;
         let key = self.build_key(&wallet);
         self.db
             .put(key, data)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        self.db
+            .put(Self::wallet_order_key(&wallet, tx), [])
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(())
+    }
+
+    fn remove_from_txs_index(&self, wallet: String, tx: &Tx) -> Result<(), std::io::Error> {
+        self.db
+            .delete(Self::wallet_order_key(&wallet, tx))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        let mut txs = self.find_wallet_txs_hashes(wallet.clone())?;
+        if txs.remove(&tx.hash_str()) {
+            let key = self.build_key(&wallet);
+            if txs.is_empty() {
+                self.db
+                    .delete(key)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            } else {
+                self.db
+                    .put(key, serde_json::to_vec(&txs)?)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            }
+        }
         Ok(())
     }
 
@@ -40,16 +68,33 @@ impl TxStorage {
             String::from(NO_BLOCK_IDX)
         };
         let mut hashes = self.find_hashes_by_block_idx(idx.clone())?;
-        hashes.insert(tx.hash_str());
+        if !hashes.contains(&tx.hash_str()) {
+            hashes.push(tx.hash_str());
+        }
         let data = serde_json::to_vec(&hashes)?;
         let key = self.build_key(&idx);
         self.db
             .put(key, &data)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if tx.block.is_some() {
+            self.remove_from_pending(&tx.hash_str())?;
+        }
+        Ok(())
+    }
+
+    fn remove_from_pending(&self, tx_hash: &str) -> Result<(), std::io::Error> {
+        let mut hashes = self.find_hashes_by_block_idx(String::from(NO_BLOCK_IDX))?;
+        if let Some(position) = hashes.iter().position(|hash| hash == tx_hash) {
+            hashes.remove(position);
+            let data = serde_json::to_vec(&hashes)?;
+            self.db
+                .put(self.build_key(NO_BLOCK_IDX), data)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
         Ok(())
     }
 
-    fn find_hashes_by_block_idx(&self, idx: String) -> Result<HashSet<String>, std::io::Error> {
+    pub fn find_hashes_by_block_idx(&self, idx: String) -> Result<Vec<String>, std::io::Error> {
         let key = self.build_key(&idx);
         if let Some(hashes) = self
             .db
@@ -59,7 +104,7 @@ impl TxStorage {
             Ok(serde_json::from_slice(&hashes)
                 .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?)
         } else {
-            Ok(HashSet::new())
+            Ok(Vec::new())
         }
     }
 
@@ -88,6 +133,64 @@ impl TxStorage {
         Ok(txs)
     }
 
+    // Newest first, read from the wallet order index without loading the other txs.
+    pub fn find_wallet_txs_page(
+        &self,
+        wallet: &str,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<Tx>, std::io::Error> {
+        let prefix = format!("{}{}.", WALLET_ORDER_PREFIX, wallet);
+        let last = format!("{}{}/", WALLET_ORDER_PREFIX, wallet);
+        let mut options = ReadOptions::default();
+        options.set_iterate_lower_bound(prefix.as_bytes());
+        let keys = self.db.iterator_opt(
+            IteratorMode::From(last.as_bytes(), Direction::Reverse),
+            options,
+        );
+        let mut txs = Vec::new();
+        for item in keys.skip(offset as usize).take(limit as usize) {
+            let (key, _) =
+                item.map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            let Some(order) = key.strip_prefix(prefix.as_bytes()) else {
+                break;
+            };
+            if let Some(tx) = self.find_by_hash(Self::ordered_hash(order)?)? {
+                txs.push(tx);
+            }
+        }
+        Ok(txs)
+    }
+
+    fn ordered_hash(order: &[u8]) -> Result<String, std::io::Error> {
+        std::str::from_utf8(order)
+            .ok()
+            .and_then(|order| order.split_once('.'))
+            .filter(|(timestamp, hash)| {
+                timestamp.len() == 20
+                    && timestamp.bytes().all(|b| b.is_ascii_digit())
+                    && !hash.is_empty()
+            })
+            .map(|(_, hash)| String::from(hash))
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!(
+                        "Invalid wallet order key {}",
+                        String::from_utf8_lossy(order)
+                    ),
+                )
+            })
+    }
+
+    pub fn count_wallet_txs(&self, wallet: String) -> Result<u64, std::io::Error> {
+        Ok(self.find_wallet_txs_hashes(wallet)?.len() as u64)
+    }
+
+    pub fn is_wallet_tx(&self, wallet: String, tx_hash: &str) -> Result<bool, std::io::Error> {
+        Ok(self.find_wallet_txs_hashes(wallet)?.contains(tx_hash))
+    }
+
     fn find_wallet_txs_hashes(&self, wallet: String) -> Result<HashSet<String>, std::io::Error> {
         let key = self.build_key(&wallet);
         match self
@@ -100,8 +203,12 @@ impl TxStorage {
         }
     }
 
+    pub fn find_pending_hashes(&self) -> Result<Vec<String>, std::io::Error> {
+        self.find_hashes_by_block_idx(String::from(NO_BLOCK_IDX))
+    }
+
     pub fn find_pending(&self) -> Result<Vec<Tx>, std::io::Error> {
-        let hashes = self.find_hashes_by_block_idx(String::from(NO_BLOCK_IDX))?;
+        let hashes = self.find_pending_hashes()?;
         let mut txs = Vec::new();
         for hash in hashes {
             if let Some(tx) = self.find_by_hash(hash)? {
@@ -122,14 +229,78 @@ impl TxStorage {
         Ok(txs)
     }
 
+    // Stages the deletes in `batch`, wallet indexes are edited in memory so that removals
+    // of several txs of one wallet do not overwrite each other.
+    pub fn delete_by_block_range(
+        &self,
+        batch: &mut WriteBatch,
+        from: u64,
+        to: u64,
+    ) -> Result<u64, std::io::Error> {
+        let mut reclaimed = 0;
+        let mut indexes: HashMap<String, HashSet<String>> = HashMap::new();
+        for idx in from..=to {
+            for hash in self.find_hashes_by_block_idx(idx.to_string())? {
+                let key = self.build_key(&hash);
+                if let Some(data) = self
+                    .db
+                    .get(&key)
+                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+                {
+                    let tx: Tx = serde_json::from_slice(&data)?;
+                    for wallet in [tx.from(), tx.to()] {
+                        if !indexes.contains_key(&wallet) {
+                            let hashes = self.find_wallet_txs_hashes(wallet.clone())?;
+                            indexes.insert(wallet.clone(), hashes);
+                        }
+                        if let Some(hashes) = indexes.get_mut(&wallet) {
+                            hashes.remove(&hash);
+                        }
+                        batch.delete(Self::wallet_order_key(&wallet, &tx));
+                    }
+                    batch.delete(&key);
+                    reclaimed += (key.len() + data.len()) as u64;
+                }
+            }
+            let key = self.build_key(&idx.to_string());
+            if let Some(data) = self
+                .db
+                .get(&key)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+            {
+                batch.delete(&key);
+                reclaimed += (key.len() + data.len()) as u64;
+            }
+        }
+        for (wallet, hashes) in indexes {
+            let key = self.build_key(&wallet);
+            if hashes.is_empty() {
+                batch.delete(key);
+            } else {
+                batch.put(key, serde_json::to_vec(&hashes)?);
+            }
+        }
+        Ok(reclaimed)
+    }
+
+    pub fn delete_pending(&self, tx: &Tx) -> Result<(), std::io::Error> {
+        let hash = tx.hash_str();
+        self.remove_from_pending(&hash)?;
+        self.remove_from_txs_index(tx.from(), tx)?;
+        self.remove_from_txs_index(tx.to(), tx)?;
+        self.db
+            .delete(self.build_key(&hash))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
     pub fn update_pending(&self, txs: &Vec<Tx>, idx: u64) -> Result<(), std::io::Error> {
         let mut hashes = self.find_hashes_by_block_idx(String::from(NO_BLOCK_IDX))?;
-        let mut new_idx = HashSet::new();
+        let mut new_idx = Vec::new();
         for tx in txs {
             let mut tx = tx.clone();
             tx.block = Some(idx);
-            hashes.remove(&tx.hash_str());
-            new_idx.insert(tx.hash_str());
+            hashes.retain(|hash| *hash != tx.hash_str());
+            new_idx.push(tx.hash_str());
             self.save_without_idx(&tx)?;
         }
         let hashes = serde_json::to_string(&hashes)?;
@@ -143,10 +314,122 @@ impl TxStorage {
         Ok(())
     }
 
+    pub fn rebuild_indexes(&self, blocks: &[Block]) -> Result<Vec<Tx>, std::io::Error> {
+        let mut txs = Vec::new();
+        let mut previous: HashMap<String, Vec<String>> = HashMap::new();
+        let mut corrupted = Vec::new();
+        for (key, value) in db::find_by_prefix(&self.db, &self.build_key(""))? {
+            if let Ok(tx) = serde_json::from_slice::<Tx>(&value) {
+                txs.push(tx);
+                continue;
+            }
+            match serde_json::from_slice::<Vec<String>>(&value) {
+                Ok(hashes) => {
+                    previous.insert(key, hashes);
+                }
+                Err(_) => corrupted.push(key),
+            }
+        }
+        if !corrupted.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Cannot parse {}", corrupted.join(", ")),
+            ));
+        }
+        txs.sort();
+        let mut by_block: BTreeMap<String, Vec<Tx>> = BTreeMap::new();
+        for tx in &txs {
+            let idx = tx
+                .block
+                .map_or(String::from(NO_BLOCK_IDX), |idx| idx.to_string());
+            by_block.entry(idx).or_default().push(tx.clone());
+        }
+        let roots: HashMap<String, &str> = blocks
+            .iter()
+            .map(|block| (block.idx.to_string(), block.merkle_root.as_str()))
+            .collect();
+        let mut orders = Vec::new();
+        let mut unordered = Vec::new();
+        for (idx, block_txs) in by_block {
+            let key = self.build_key(&idx);
+            let kept = previous.get(&key).cloned().unwrap_or_default();
+            match Self::restore_order(kept, block_txs, roots.get(&idx).copied()) {
+                Some(order) => orders.push((key, order)),
+                None => unordered.push(idx),
+            }
+        }
+        if !unordered.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Cannot restore the tx order of blocks {}",
+                    unordered.join(", ")
+                ),
+            ));
+        }
+        let ordered = db::find_by_prefix(&self.db, WALLET_ORDER_PREFIX)?;
+        for key in previous.keys().chain(ordered.iter().map(|(key, _)| key)) {
+            self.db
+                .delete(key)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        for tx in &txs {
+            self.add_to_txs_index(tx.from(), tx)?;
+            self.add_to_txs_index(tx.to(), tx)?;
+        }
+        for (key, order) in orders {
+            self.db
+                .put(key, serde_json::to_vec(&order)?)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(txs)
+    }
+
+    // The merkle root of the header commits to the tx order, an order is only restored if it
+    // reproduces it: the previous index, then the order blocks are produced in.
+    fn restore_order(
+        previous: Vec<String>,
+        txs: Vec<Tx>,
+        merkle_root: Option<&str>,
+    ) -> Option<Vec<String>> {
+        let mut kept: Vec<Tx> = Vec::new();
+        for hash in previous {
+            if let Some(tx) = txs.iter().find(|tx| tx.hash_str() == hash)
+                && !kept.contains(tx)
+            {
+                kept.push(tx.clone());
+            }
+        }
+        for tx in &txs {
+            if !kept.contains(tx) {
+                kept.push(tx.clone());
+            }
+        }
+        let Some(merkle_root) = merkle_root else {
+            return Some(kept.iter().map(|tx| tx.hash_str()).collect());
+        };
+        let mut by_nonce = txs.clone();
+        by_nonce.sort_by_key(|tx| tx.nonce());
+        [kept, by_nonce, txs]
+            .into_iter()
+            .find(|order| hex::encode(Block::merkle_root(order)) == merkle_root)
+            .map(|order| order.iter().map(|tx| tx.hash_str()).collect())
+    }
+
     fn build_key(&self, value: &str) -> String {
         format!("tx.{}", value)
     }
 
+    fn wallet_order_key(wallet: &str, tx: &Tx) -> String {
+        format!(
+            "{}{}.{:020}.{}",
+            WALLET_ORDER_PREFIX,
+            wallet,
+            tx.timestamp,
+            tx.hash_str()
+        )
+    }
+
     fn save_without_idx(&self, tx: &Tx) -> Result<(), std::io::Error> {
         let json = serde_json::to_vec(&tx)?;
         self.db
@@ -0,0 +1,37 @@
+use chain::stake::Stake;
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use std::sync::Arc;
+
+pub struct ValidatorStorage {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+}
+
+impl ValidatorStorage {
+    pub fn new(db: Arc<DBWithThreadMode<MultiThreaded>>) -> Self {
+        Self { db }
+    }
+
+    pub fn save(&self, idx: u64, stakes: &[Stake]) -> Result<(), std::io::Error> {
+        let data = serde_json::to_vec(stakes)?;
+        self.db
+            .put(self.build_key(idx), data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Ok(())
+    }
+
+    pub fn find(&self, idx: u64) -> Result<Option<Vec<Stake>>, std::io::Error> {
+        if let Some(data) = self
+            .db
+            .get(self.build_key(idx))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?
+        {
+            Ok(Some(serde_json::from_slice(&data)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn build_key(&self, idx: u64) -> String {
+        format!("validators.{}", idx)
+    }
+}
@@ -1,2 +0,0 @@
-pub const STAKE_WALLET: &str = "STAKE";
-pub const UNSTAKE_WALLET: &str = "UNSTAKE";
\ No newline at end of file
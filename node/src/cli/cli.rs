@@ -1,8 +1,12 @@
+use crate::blockchain::archive;
+use crate::blockchain::blockchain::Blockchain;
 use crate::blockchain::config::Config;
-use crate::net::client::Client;
+use crate::blockchain::storage::db::Access;
+use crate::cli::logging;
 use crate::{blockchain, net};
 use chain::tx;
 use clap::{Parser, Subcommand};
+use client::client::Client;
 use wallet::wallet::Wallet;
 
 #[derive(Parser)]
@@ -10,6 +14,14 @@ use wallet::wallet::Wallet;
 pub struct Cli {
     #[arg(long, value_name = "config")]
     config: Option<String>,
+    #[arg(long, help = "Open storage read-only, while a node may be running")]
+    read_only: bool,
+    #[arg(
+        long,
+        value_name = "path",
+        help = "Open storage as a secondary instance that catches up with a running node"
+    )]
+    secondary: Option<String>,
     #[command(subcommand)]
     chain: ChainCmd,
 }
@@ -29,6 +41,11 @@ pub enum ChainCmd {
         #[arg(long, value_name = "amount")]
         amount: String,
     },
+    #[clap(about = "Show receipt and status of a transaction")]
+    TxStatus {
+        #[arg(long, value_name = "hash")]
+        hash: String,
+    },
     #[clap(about = "Stake some value")]
     Stake {
         #[arg(long, value_name = "from")]
@@ -36,6 +53,31 @@ pub enum ChainCmd {
         #[arg(long, value_name = "amount")]
         amount: String,
     },
+    #[clap(about = "Export chain blocks to a file")]
+    Export {
+        #[arg(long, value_name = "path")]
+        path: String,
+        #[arg(long, help = "Compress exported blocks with gzip")]
+        compress: bool,
+    },
+    #[clap(about = "Import chain blocks from a file")]
+    Import {
+        #[arg(long, value_name = "path")]
+        path: String,
+    },
+    #[clap(about = "Inspect and repair chain storage")]
+    Db {
+        #[command(subcommand)]
+        cmd: DbCmd,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DbCmd {
+    #[clap(about = "Check blocks, transactions and indexes")]
+    Verify,
+    #[clap(about = "Rebuild secondary indexes from block data")]
+    Reindex,
 }
 
 async fn create_wallet(config: &blockchain::config::Config) -> Result<(), std::io::Error> {
@@ -64,22 +106,91 @@ async fn new_tx(
     println!("Enter password:");
     let password = rpassword::read_password()?;
     let wallet = Wallet::read(&config.keystore_path(), from.as_str(), password.as_bytes())?;
-    let mut client = Client::new(config).await?;
-    let nonce = client.get_nonce(from).await;
+    let client = Client::new(&config.client_config()).await?;
+    let nonce = client.get_nonce(from).await?;
     let tx = tx::Tx::new(&wallet, to, amount, nonce + 1)?;
     println!("Tx created: {:?}", tx);
-    if client.send_tx(&tx).await {
-        println!("Transaction successfully submitted");
-    } else {
-        println!("Transaction invalid");
+    client.send_tx(&tx).await?;
+    println!("Transaction successfully submitted");
+    Ok(())
+}
+
+async fn tx_status(config: &Config, hash: String) -> Result<(), Box<dyn std::error::Error>> {
+    let client = Client::new(&config.client_config()).await?;
+    match client.get_receipt(hash).await? {
+        Some(receipt) => {
+            println!("Status: {:?}, success: {}", receipt.status, receipt.success);
+            if let (Some(block), Some(index)) = (receipt.block, receipt.index) {
+                println!("Block: {}, index: {}", block, index);
+            }
+            if let Some(reason) = receipt.reason {
+                println!("Reason: {}", reason);
+            }
+        }
+        None => println!("Transaction not found"),
     }
     Ok(())
 }
 
+fn open_blockchain(config: &Config, access: &Access) -> Result<Blockchain, std::io::Error> {
+    let blockchain = Blockchain::open(config, access)?;
+    if let Access::Secondary(_) = access {
+        blockchain.catch_up()?;
+    }
+    Ok(blockchain)
+}
+
+async fn export_chain(
+    config: &Config,
+    access: &Access,
+    path: String,
+    compress: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let blockchain = open_blockchain(config, access)?;
+    let count = archive::export(&blockchain, &path, compress)?;
+    println!("Exported {} blocks to {}", count, path);
+    Ok(())
+}
+
+async fn import_chain(config: &Config, path: String) -> Result<(), Box<dyn std::error::Error>> {
+    let blockchain = Blockchain::new(Wallet::new(), config)?;
+    let count = archive::import(&blockchain, &path)?;
+    println!("Imported {} blocks from {}", count, path);
+    Ok(())
+}
+
+async fn verify_db(config: &Config, access: &Access) -> Result<(), Box<dyn std::error::Error>> {
+    let blockchain = open_blockchain(config, access)?;
+    let report = blockchain.verify()?;
+    for error in &report.errors {
+        println!("{}", error);
+    }
+    println!(
+        "Checked {} blocks and {} transactions, found {} problems",
+        report.blocks,
+        report.txs,
+        report.errors.len()
+    );
+    if !report.errors.is_empty() {
+        return Err("Database integrity check failed, run `db reindex` to rebuild indexes".into());
+    }
+    Ok(())
+}
+
+async fn reindex_db(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    let blockchain = Blockchain::new(Wallet::new(), config)?;
+    let report = blockchain.reindex()?;
+    println!(
+        "Reindexed {} blocks and {} transactions",
+        report.blocks, report.txs
+    );
+    Ok(())
+}
+
 async fn start_node(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
     let mut node = net::node::Node::new(config)?;
-    if !config.nodes().is_empty() {
-        node.sync(config).await?;
+    if !config.nodes().is_empty() && config.fast_sync() {
+        node.fast_sync(config).await?;
     }
     node.start().await?;
     Ok(())
@@ -92,11 +203,34 @@ pub async fn start_cli() -> Result<(), Box<dyn std::error::Error>> {
     } else {
         Config::from_file(blockchain::config::DEFAULT_CONFIG_PATH)?
     };
+    let _guard = logging::init(&config)?;
+    let access = match (cli.read_only, cli.secondary) {
+        (_, Some(secondary_path)) => Access::Secondary(secondary_path),
+        (true, None) => Access::ReadOnly,
+        (false, None) => Access::Primary,
+    };
+    if access != Access::Primary
+        && !matches!(
+            cli.chain,
+            ChainCmd::Export { .. } | ChainCmd::Db { cmd: DbCmd::Verify }
+        )
+    {
+        return Err("Only export and db verify can use read-only or secondary storage".into());
+    }
     match cli.chain {
         ChainCmd::Create => create_wallet(&config).await?,
         ChainCmd::Stake { from, amount } => stake(&config, from, amount).await?,
         ChainCmd::Start => start_node(&config).await?,
         ChainCmd::Tx { from, to, amount } => new_tx(&config, from, to, amount).await?,
+        ChainCmd::TxStatus { hash } => tx_status(&config, hash).await?,
+        ChainCmd::Export { path, compress } => {
+            export_chain(&config, &access, path, compress).await?
+        }
+        ChainCmd::Import { path } => import_chain(&config, path).await?,
+        ChainCmd::Db { cmd: DbCmd::Verify } => verify_db(&config, &access).await?,
+        ChainCmd::Db {
+            cmd: DbCmd::Reindex,
+        } => reindex_db(&config).await?,
     }
     Ok(())
 }
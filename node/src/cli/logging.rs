@@ -0,0 +1,84 @@
+use crate::blockchain::config::{Config, LogFormat, LogRotation};
+use std::path::Path;
+use tracing::Subscriber;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{EnvFilter, Layer, fmt};
+
+pub type BoxedSubscriber = Box<dyn Subscriber + Send + Sync>;
+
+pub fn init(config: &Config) -> Result<Option<WorkerGuard>, std::io::Error> {
+    let directives = std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or(config.log_level());
+    let (subscriber, guard) = subscriber(config, &directives, std::io::stdout)?;
+    tracing::subscriber::set_global_default(subscriber).map_err(std::io::Error::other)?;
+    Ok(guard)
+}
+
+pub fn subscriber<W>(
+    config: &Config,
+    directives: &str,
+    console: W,
+) -> Result<(BoxedSubscriber, Option<WorkerGuard>), std::io::Error>
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let filter = EnvFilter::try_new(directives)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let (file, guard) = match config.log_file() {
+        Some(path) => {
+            let (writer, guard) =
+                tracing_appender::non_blocking(appender(&path, config.log_rotation())?);
+            (Some(layer(config.log_format(), writer, false)), Some(guard))
+        }
+        None => (None, None),
+    };
+    let subscriber = tracing_subscriber::registry()
+        .with(filter)
+        .with(layer(config.log_format(), console, true))
+        .with(file);
+    Ok((Box::new(subscriber), guard))
+}
+
+fn layer<S, W>(format: LogFormat, writer: W, ansi: bool) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    match format {
+        LogFormat::Pretty => fmt::layer().with_writer(writer).with_ansi(ansi).boxed(),
+        LogFormat::Json => fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(writer)
+            .boxed(),
+    }
+}
+
+fn appender(path: &str, rotation: LogRotation) -> Result<RollingFileAppender, std::io::Error> {
+    let path = Path::new(path);
+    let Some(file_name) = path.file_name() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid log file {}", path.display()),
+        ));
+    };
+    let directory = path
+        .parent()
+        .filter(|directory| !directory.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let rotation = match rotation {
+        LogRotation::Never => Rotation::NEVER,
+        LogRotation::Minutely => Rotation::MINUTELY,
+        LogRotation::Hourly => Rotation::HOURLY,
+        LogRotation::Daily => Rotation::DAILY,
+    };
+    RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(file_name.to_string_lossy())
+        .build(directory)
+        .map_err(std::io::Error::other)
+}
@@ -1,3 +1,4 @@
+mod api;
 mod blockchain;
 mod cli;
 mod net;
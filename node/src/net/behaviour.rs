@@ -1,46 +1,37 @@
+use crate::blockchain::pruning::History;
+use chain::snapshot::AccountState;
+use chain::wire::{
+    AccountRequest, BlockRequest, BlockResponse, BlocksRequest, BlocksResponse, MempoolRequest,
+    MempoolResponse, NonceRequest, NonceResponse, SnapshotRequest, SnapshotResponse, Status,
+    TxResponse,
+};
 use libp2p::swarm::NetworkBehaviour;
-use libp2p::{gossipsub, mdns, request_response};
+use libp2p::swarm::behaviour::toggle::Toggle;
+use libp2p::{allow_block_list, gossipsub, identify, kad, mdns};
+use network::protocol::{self, Versioned};
 use serde::{Deserialize, Serialize};
-use chain::tx::Tx;
-use crate::blockchain::block::Block;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NonceRequest {
-    pub address: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NonceResponse {
-    pub nonce: u64,
-}
+pub struct HistoryRequest {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TxResponse {
-    pub error: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BlockRequest {
-    pub idx: u64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BlockResponse {
-    pub block: Option<Block>,
-}
-
-#[derive(NetworkBehaviour)]
-pub struct ClientBehaviour {
-    pub nonce: request_response::json::Behaviour<NonceRequest, NonceResponse>,
-    pub tx: request_response::json::Behaviour<Tx, TxResponse>,
-    pub find_block: request_response::json::Behaviour<BlockRequest, BlockResponse>,
-}
+impl Versioned for HistoryRequest {}
+impl Versioned for History {}
 
 #[derive(NetworkBehaviour)]
 pub struct NodeBehaviour {
     pub gossipsub: gossipsub::Behaviour,
     pub mdns: mdns::tokio::Behaviour,
-    pub nonce: request_response::json::Behaviour<NonceRequest, NonceResponse>,
-    pub tx: request_response::json::Behaviour<chain::tx::Tx, TxResponse>,
-    pub find_block: request_response::json::Behaviour<BlockRequest, BlockResponse>,
+    pub blocked_peers: allow_block_list::Behaviour<allow_block_list::BlockedPeers>,
+    pub allowed_peers: Toggle<allow_block_list::Behaviour<allow_block_list::AllowedPeers>>,
+    pub kademlia: kad::Behaviour<kad::store::MemoryStore>,
+    pub identify: identify::Behaviour,
+    pub nonce: protocol::Behaviour<NonceRequest, NonceResponse>,
+    pub tx: protocol::Behaviour<chain::tx::Tx, TxResponse>,
+    pub find_block: protocol::Behaviour<BlockRequest, BlockResponse>,
+    pub snapshot: protocol::Behaviour<SnapshotRequest, SnapshotResponse>,
+    pub history: protocol::Behaviour<HistoryRequest, History>,
+    pub status: protocol::Behaviour<Status, Status>,
+    pub blocks: protocol::Behaviour<BlocksRequest, BlocksResponse>,
+    pub mempool: protocol::Behaviour<MempoolRequest, MempoolResponse>,
+    pub account: protocol::Behaviour<AccountRequest, AccountState>,
 }
@@ -0,0 +1,87 @@
+use libp2p::Multiaddr;
+use libp2p::multiaddr::Protocol;
+use libp2p::swarm::ConnectionId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+pub const DISCOVERY_INTERVAL: Duration = Duration::from_secs(60);
+
+// Loopback and unspecified addresses point back at the listener, not at the peer.
+pub fn routable(address: &Multiaddr) -> bool {
+    match address.iter().next() {
+        Some(Protocol::Ip4(ip)) => !ip.is_loopback() && !ip.is_unspecified(),
+        Some(Protocol::Ip6(ip)) => !ip.is_loopback() && !ip.is_unspecified(),
+        _ => true,
+    }
+}
+
+struct Attempt {
+    failures: u32,
+    next: Option<Instant>,
+}
+
+pub struct Redial {
+    attempts: HashMap<Multiaddr, Attempt>,
+    dials: HashMap<ConnectionId, Multiaddr>,
+}
+
+impl Redial {
+    pub fn new(addresses: Vec<Multiaddr>) -> Self {
+        let now = Instant::now();
+        Self {
+            attempts: addresses
+                .into_iter()
+                .map(|address| {
+                    (
+                        address,
+                        Attempt {
+                            failures: 0,
+                            next: Some(now),
+                        },
+                    )
+                })
+                .collect(),
+            dials: HashMap::new(),
+        }
+    }
+
+    pub fn backoff(failures: u32) -> Duration {
+        BASE_BACKOFF
+            .saturating_mul(2u32.saturating_pow(failures))
+            .min(MAX_BACKOFF)
+    }
+
+    pub fn due(&mut self, now: Instant) -> Vec<Multiaddr> {
+        let mut due = Vec::new();
+        for (address, attempt) in self.attempts.iter_mut() {
+            if attempt.next.is_some_and(|next| next <= now) {
+                attempt.next = None;
+                due.push(address.clone());
+            }
+        }
+        due
+    }
+
+    pub fn dialing(&mut self, connection_id: ConnectionId, address: Multiaddr) {
+        self.dials.insert(connection_id, address);
+    }
+
+    pub fn connected(&mut self, connection_id: ConnectionId) {
+        if let Some(address) = self.dials.get(&connection_id)
+            && let Some(attempt) = self.attempts.get_mut(address)
+        {
+            attempt.failures = 0;
+        }
+    }
+
+    pub fn disconnected(&mut self, connection_id: ConnectionId, now: Instant) -> Option<Duration> {
+        let address = self.dials.remove(&connection_id)?;
+        let attempt = self.attempts.get_mut(&address)?;
+        let backoff = Self::backoff(attempt.failures);
+        attempt.failures = attempt.failures.saturating_add(1);
+        attempt.next = Some(now + backoff);
+        Some(backoff)
+    }
+}
@@ -0,0 +1,61 @@
+use crate::blockchain::blockchain::Blockchain;
+use chain::tx::Tx;
+use std::collections::HashSet;
+
+pub const MAX_INVENTORY: usize = 4096;
+pub const MAX_TXS: usize = 256;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Applied {
+    pub added: usize,
+    pub invalid: usize,
+}
+
+pub fn inventory(blockchain: &Blockchain) -> Result<Vec<String>, std::io::Error> {
+    let mut hashes = blockchain.pending_tx_hashes()?;
+    hashes.truncate(MAX_INVENTORY);
+    Ok(hashes)
+}
+
+pub fn missing(blockchain: &Blockchain, hashes: &[String]) -> Result<Vec<String>, std::io::Error> {
+    let mut seen = HashSet::new();
+    let mut missing = Vec::new();
+    for hash in hashes.iter().take(MAX_INVENTORY) {
+        if missing.len() == MAX_TXS {
+            break;
+        }
+        if seen.insert(hash) && blockchain.find_tx(hash.clone())?.is_none() {
+            missing.push(hash.clone());
+        }
+    }
+    Ok(missing)
+}
+
+pub fn find_pending(blockchain: &Blockchain, hashes: &[String]) -> Result<Vec<Tx>, std::io::Error> {
+    let mut txs = Vec::new();
+    for hash in hashes.iter().take(MAX_TXS) {
+        if let Some(tx) = blockchain.find_tx(hash.clone())?
+            && tx.block.is_none()
+        {
+            txs.push(tx);
+        }
+    }
+    Ok(txs)
+}
+
+pub fn apply(blockchain: &Blockchain, mut txs: Vec<Tx>) -> Applied {
+    txs.truncate(MAX_TXS);
+    txs.sort_by_key(|tx| (tx.from(), tx.nonce()));
+    let mut applied = Applied::default();
+    for mut tx in txs {
+        tx.block = None;
+        if !tx.valid() {
+            applied.invalid += 1;
+            continue;
+        }
+        if blockchain.add_tx(&tx).is_ok() {
+            applied.added += 1;
+        }
+    }
+    applied
+}
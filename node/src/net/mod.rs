@@ -1,3 +1,11 @@
-pub mod node;
 mod behaviour;
-pub mod client;
\ No newline at end of file
+pub mod discovery;
+pub mod mempool;
+pub mod node;
+pub mod orphan;
+pub mod private_network;
+pub mod protocol;
+pub mod reputation;
+pub mod status;
+pub mod sync;
+pub mod validation;
@@ -1,132 +1,279 @@
-use crate::blockchain::block::Block;
+use crate::api::explorer::Explorer;
+use crate::api::metrics::{self, Metrics};
+use crate::api::rpc::Rpc;
+use crate::api::{server, ws};
 use crate::blockchain::blockchain::Blockchain;
 use crate::blockchain::config::Config;
+use crate::net::behaviour::{NodeBehaviour, NodeBehaviourEvent};
+use crate::net::discovery::{self, DISCOVERY_INTERVAL, Redial};
+use crate::net::mempool;
+use crate::net::orphan::{MAX_ORPHAN_AGE, MAX_ORPHANS, OrphanPool};
+use crate::net::private_network;
+use crate::net::protocol::{self, ProtocolExt};
+use crate::net::reputation::{Offence, PROOF_REQUEST_COST, Reputation};
+use crate::net::status::{PeerStatus, StatusExt};
+use crate::net::sync::{MAX_RANGE, SyncManager};
+use crate::net::validation;
+use chain::block::Block;
+use chain::headers::HeaderChain;
+use chain::proof::{AccountProof, TxProof};
 use chain::tx::Tx;
-use crate::net::behaviour::{
-    BlockRequest, BlockResponse, NodeBehaviour, NodeBehaviourEvent, NonceRequest, NonceResponse,
-    TxResponse,
+use chain::wire::{
+    self, BlockRequest, BlockResponse, BlocksRequest, BlocksResponse, HEADERS_BATCH,
+    MempoolRequest, MempoolResponse, NonceResponse, STATUS_VERSION, SnapshotRequest,
+    SnapshotResponse, Status, TxResponse,
 };
+use client::client::Client;
 use futures::StreamExt;
-use libp2p::gossipsub::IdentTopic;
+use libp2p::gossipsub::{IdentTopic, MessageAcceptance, PeerScoreParams, TopicScoreParams};
 use libp2p::identity::Keypair;
 use libp2p::swarm::SwarmEvent;
+use libp2p::swarm::behaviour::toggle::Toggle;
+use libp2p::swarm::dial_opts::DialOpts;
 use libp2p::{
-    StreamProtocol, Swarm, gossipsub, identity, mdns, noise, request_response, tcp, yamux,
+    Multiaddr, PeerId, StreamProtocol, Swarm, allow_block_list, gossipsub, identify, kad, mdns,
+    noise, request_response, tcp, yamux,
 };
+use std::collections::HashMap;
+use std::io::ErrorKind;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::select;
 use tokio::sync::mpsc;
 use tokio_cron_scheduler::{Job, JobScheduler};
+use tracing::{Instrument, debug, info, warn};
 use wallet::wallet::Wallet;
-use crate::net::client::Client;
+
+const TX_TOPIC: &str = "txs";
+const BLOCK_TOPIC: &str = "block";
+const KAD_PROTOCOL: &str = "/xhcg/kad/1.0.0";
+const IDENTIFY_PROTOCOL: &str = "/xhcg/1.0.0";
 
 pub struct Node {
     port: i64,
+    quic: bool,
     swarm: Swarm<NodeBehaviour>,
     blockchain: Arc<Blockchain>,
     tx_topic: IdentTopic,
     block_topic: IdentTopic,
+    reputation: Reputation,
+    redial: Redial,
+    chain_id: String,
+    sync: SyncManager,
+    sync_requests: HashMap<request_response::OutboundRequestId, u64>,
+    orphans: OrphanPool,
+    orphan_requests: HashMap<request_response::OutboundRequestId, String>,
+    fork_requests: HashMap<request_response::OutboundRequestId, u64>,
+    requests: HashMap<(&'static str, request_response::OutboundRequestId), Instant>,
+    rpc_address: Option<String>,
+    metrics_address: Option<String>,
+    metrics: Arc<Metrics>,
 }
 
 impl Node {
     pub fn new(config: &Config) -> Result<Self, Box<dyn std::error::Error>> {
-        println!("Enter password:");
-        let password = rpassword::read_password().unwrap();
+        let password = rpassword::prompt_password("Enter password: ")?;
         let wallet = Wallet::read(
             &config.keystore_path(),
             &config.validator(),
             password.as_bytes(),
         )?;
+        let metrics = Arc::new(Metrics::new());
+        let blockchain = Blockchain::new(wallet.clone(), config)?.with_metrics(metrics.clone());
         Ok(Self {
             port: config.port(),
-            swarm: Self::build_swarm(&wallet)?,
-            blockchain: Arc::new(Blockchain::new(wallet.clone(), &config)?),
-            tx_topic: IdentTopic::new("txs"),
-            block_topic: IdentTopic::new("block"),
+            swarm: Self::build_swarm(&wallet, config)?,
+            quic: config.psk_path().is_none(),
+            blockchain: Arc::new(blockchain),
+            tx_topic: IdentTopic::new(TX_TOPIC),
+            block_topic: IdentTopic::new(BLOCK_TOPIC),
+            reputation: Reputation::new(
+                Duration::from_secs(config.ban_duration()),
+                config.request_rate(),
+            ),
+            redial: Redial::new(Self::bootstrap_addresses(config)),
+            chain_id: config.chain_id(),
+            sync: SyncManager::new(),
+            sync_requests: HashMap::new(),
+            orphans: OrphanPool::new(MAX_ORPHANS, MAX_ORPHAN_AGE),
+            orphan_requests: HashMap::new(),
+            fork_requests: HashMap::new(),
+            requests: HashMap::new(),
+            rpc_address: config.rpc_address(),
+            metrics_address: config.metrics_address(),
+            metrics,
         })
     }
 
-    fn build_swarm(validator: &Wallet) -> Result<Swarm<NodeBehaviour>, Box<dyn std::error::Error>> {
-        let secret = identity::ecdsa::SecretKey::try_from_bytes(validator.secret())
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        let keypair = identity::ecdsa::Keypair::from(secret);
-        let _ = tracing_subscriber::fmt()
-            .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
-            .try_init();
-        let swarm = libp2p::SwarmBuilder::with_existing_identity(Keypair::from(keypair))
-            .with_tokio()
-            .with_tcp(
-                tcp::Config::default(),
-                noise::Config::new,
-                yamux::Config::default,
-            )?
-            .with_quic()
-            .with_behaviour(|key| {
-                let gossibsub_config = gossipsub::ConfigBuilder::default()
-                    .heartbeat_interval(Duration::from_secs(10))
-                    .validation_mode(gossipsub::ValidationMode::Strict)
-                    .build()
-                    .map_err(tokio::io::Error::other)?;
-                let gossipsub = gossipsub::Behaviour::new(
-                    gossipsub::MessageAuthenticity::Signed(key.clone()),
-                    gossibsub_config,
-                )?;
-                let mdns = mdns::tokio::Behaviour::new(
-                    mdns::Config::default(),
-                    key.public().to_peer_id(),
-                )?;
-                let nonce_behaviour =
-                    request_response::json::Behaviour::<NonceRequest, NonceResponse>::new(
-                        [(
-                            StreamProtocol::new("/nonce/0.0.1"),
-                            request_response::ProtocolSupport::Full,
-                        )],
-                        request_response::Config::default(),
-                    );
-                let tx_behaviour = request_response::json::Behaviour::<Tx, TxResponse>::new(
-                    [(
-                        StreamProtocol::new("/tx/0.0.1"),
-                        request_response::ProtocolSupport::Full,
-                    )],
-                    request_response::Config::default(),
+    fn build_swarm(
+        validator: &Wallet,
+        config: &Config,
+    ) -> Result<Swarm<NodeBehaviour>, Box<dyn std::error::Error>> {
+        let keypair = private_network::keypair(validator)?;
+        let allowed_peers = private_network::allowed_peers(&config.allowed_validators())?;
+        let builder = libp2p::SwarmBuilder::with_existing_identity(keypair).with_tokio();
+        let swarm = match config.psk_path() {
+            Some(psk_path) => {
+                let psk = private_network::read_psk(&psk_path)?;
+                info!(
+                    "Private network mode, key fingerprint: {}",
+                    psk.fingerprint()
                 );
-                let find_block_behaviour =
-                    request_response::json::Behaviour::<BlockRequest, BlockResponse>::new(
-                        [(
-                            StreamProtocol::new("/block/0.0.1"),
-                            request_response::ProtocolSupport::Full,
-                        )],
-                        request_response::Config::default(),
-                    );
-                Ok(NodeBehaviour {
-                    gossipsub,
-                    mdns,
-                    nonce: nonce_behaviour,
-                    tx: tx_behaviour,
-                    find_block: find_block_behaviour,
-                })
-            })?
-            .build();
+                builder
+                    .with_other_transport(|key| private_network::transport(key, psk))?
+                    .with_behaviour(|key| Self::build_behaviour(key, &allowed_peers))?
+                    .build()
+            }
+            None => builder
+                .with_tcp(
+                    tcp::Config::default(),
+                    noise::Config::new,
+                    yamux::Config::default,
+                )?
+                .with_quic()
+                .with_behaviour(|key| Self::build_behaviour(key, &allowed_peers))?
+                .build(),
+        };
         Ok(swarm)
     }
 
-    pub async fn sync(&self, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-        let mut synced = false;
-        let mut client = Client::new(config).await?;
-        while !synced {
-            let latest_block = self.blockchain.find_latest()?;
-            match client.find_block_by_idx(latest_block.idx + 1).await {
-                Some(block) => {
-                    println!("block: {:?}", block);
-                    self.blockchain.add_block(&block)?;
-                },
-                None => synced = true,
+    fn build_behaviour(
+        key: &Keypair,
+        allowed_peers: &[PeerId],
+    ) -> Result<NodeBehaviour, Box<dyn std::error::Error + Send + Sync>> {
+        let gossibsub_config = gossipsub::ConfigBuilder::default()
+            .heartbeat_interval(Duration::from_secs(10))
+            .validation_mode(gossipsub::ValidationMode::Strict)
+            .validate_messages()
+            .build()
+            .map_err(tokio::io::Error::other)?;
+        let mut gossipsub = gossipsub::Behaviour::new(
+            gossipsub::MessageAuthenticity::Signed(key.clone()),
+            gossibsub_config,
+        )?;
+        gossipsub.with_peer_score(Self::peer_score_params(), Default::default())?;
+        let mdns = mdns::tokio::Behaviour::new(mdns::Config::default(), key.public().to_peer_id())?;
+        let mut kademlia = kad::Behaviour::with_config(
+            key.public().to_peer_id(),
+            kad::store::MemoryStore::new(key.public().to_peer_id()),
+            kad::Config::new(StreamProtocol::new(KAD_PROTOCOL)),
+        );
+        kademlia.set_mode(Some(kad::Mode::Server));
+        let identify = identify::Behaviour::new(identify::Config::new(
+            String::from(IDENTIFY_PROTOCOL),
+            key.public(),
+        ));
+        Ok(NodeBehaviour {
+            gossipsub,
+            mdns,
+            blocked_peers: allow_block_list::Behaviour::default(),
+            allowed_peers: Toggle::from((!allowed_peers.is_empty()).then(|| {
+                let mut allowed = allow_block_list::Behaviour::default();
+                for peer in allowed_peers {
+                    allowed.allow_peer(*peer);
+                }
+                allowed
+            })),
+            kademlia,
+            identify,
+            nonce: protocol::NONCE.behaviour(),
+            tx: protocol::TX.behaviour(),
+            find_block: protocol::BLOCK.behaviour(),
+            snapshot: protocol::SNAPSHOT.behaviour(),
+            history: protocol::HISTORY.behaviour(),
+            status: protocol::STATUS.behaviour(),
+            blocks: protocol::BLOCKS.behaviour(),
+            mempool: protocol::MEMPOOL.behaviour(),
+            account: protocol::ACCOUNT.behaviour(),
+        })
+    }
+
+    fn bootstrap_addresses(config: &Config) -> Vec<Multiaddr> {
+        let mut addresses = Vec::new();
+        for node in config.nodes() {
+            match node.parse() {
+                Ok(address) => addresses.push(address),
+                Err(e) => warn!("Invalid bootstrap address {}: {}", node, e),
             }
         }
+        addresses
+    }
+
+    fn peer_score_params() -> PeerScoreParams {
+        let topic_params = TopicScoreParams {
+            mesh_message_deliveries_weight: 0.0,
+            mesh_failure_penalty_weight: 0.0,
+            invalid_message_deliveries_weight: -100.0,
+            ..Default::default()
+        };
+        let mut params = PeerScoreParams {
+            app_specific_weight: 1.0,
+            ..Default::default()
+        };
+        for topic in [TX_TOPIC, BLOCK_TOPIC] {
+            params
+                .topics
+                .insert(IdentTopic::new(topic).hash(), topic_params.clone());
+        }
+        params
+    }
+
+    pub async fn fast_sync(&self, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+        if self.blockchain.find_latest()?.idx > 0 {
+            return Ok(());
+        }
+        let client = Client::new(&config.client_config()).await?;
+        let Some(manifest) = client.get_snapshot_manifest().await? else {
+            info!("No snapshot available, syncing from genesis");
+            return Ok(());
+        };
+        let headers = Self::sync_headers(&self.blockchain, &client, manifest.height).await?;
+        let mut chunks = Vec::new();
+        for idx in 0..manifest.chunks.len() as u64 {
+            match client.get_snapshot_chunk(manifest.height, idx).await? {
+                Some(chunk) => chunks.push(chunk),
+                None => {
+                    return Err(Box::new(std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("Snapshot chunk {} not found", idx),
+                    )));
+                }
+            }
+        }
+        self.blockchain
+            .apply_snapshot(&manifest, &chunks, &headers)?;
+        info!("Snapshot applied at block {}", manifest.height);
         Ok(())
     }
 
+    // Follows the headers from the local head, so the snapshot is anchored to verified blocks.
+    async fn sync_headers(
+        blockchain: &Blockchain,
+        client: &Client,
+        height: u64,
+    ) -> Result<HeaderChain, Box<dyn std::error::Error>> {
+        let mut chain = HeaderChain::new(blockchain.find_latest()?, blockchain.stakes()?);
+        while chain.height() < height {
+            let count = HEADERS_BATCH.min(height - chain.height());
+            let headers = client.get_headers(chain.height() + 1, count).await?;
+            if headers.is_empty() {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Header {} not found", chain.height() + 1),
+                )));
+            }
+            for header in headers {
+                let stakes = if chain.validators_changed(&header) {
+                    client.get_validators(header.idx).await?
+                } else {
+                    None
+                };
+                chain.append(&header, stakes)?;
+            }
+        }
+        Ok(chain)
+    }
+
     pub async fn start(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         self.swarm
             .behaviour_mut()
@@ -139,10 +286,39 @@ impl Node {
 
         self.swarm
             .listen_on(format!("/ip4/0.0.0.0/tcp/{}", self.port).parse()?)?;
-        self.swarm
-            .listen_on(format!("/ip4/0.0.0.0/udp/{}/quic-v1", self.port).parse()?)?;
+        if self.quic {
+            self.swarm
+                .listen_on(format!("/ip4/0.0.0.0/udp/{}/quic-v1", self.port).parse()?)?;
+        }
+
+        self.dial_known_peers()?;
+        self.bootstrap_kademlia();
 
         let (validator_tx, mut validator_rx) = mpsc::channel::<Block>(100);
+        let (rpc_tx, mut rpc_rx) = mpsc::channel::<Tx>(100);
+
+        if let Some(address) = self.rpc_address.clone() {
+            let router = Rpc::new(Arc::clone(&self.blockchain), rpc_tx.clone())
+                .router()
+                .merge(ws::router(Arc::clone(&self.blockchain)))
+                .merge(Explorer::new(Arc::clone(&self.blockchain)).router());
+            let listener = server::bind(&address).await?;
+            tokio::spawn(async move {
+                if let Err(e) = server::serve(listener, router).await {
+                    warn!("RPC server stopped: {}", e);
+                }
+            });
+        }
+
+        if let Some(address) = self.metrics_address.clone() {
+            let router = metrics::router(Arc::clone(&self.blockchain), Arc::clone(&self.metrics));
+            let listener = server::bind(&address).await?;
+            tokio::spawn(async move {
+                if let Err(e) = server::serve(listener, router).await {
+                    warn!("Metrics server stopped: {}", e);
+                }
+            });
+        }
 
         let blockchain = Arc::clone(&self.blockchain);
 
@@ -153,32 +329,74 @@ impl Node {
                 let blockchain = Arc::clone(&blockchain);
                 Box::pin(async move {
                     match blockchain.proof_of_stake() {
-                        Ok(block) => match validator_tx.send(block).await {
-                            Err(e) => println!("Error sending block: {:?}", e),
-                            _ => {}
-                        },
-                        Err(e) => println!("Cannot create block: {}", e),
+                        Ok(block) => {
+                            if let Err(e) = validator_tx.send(block).await {
+                                warn!("Error sending block: {:?}", e);
+                            }
+                        }
+                        Err(e) => warn!("Cannot create block: {}", e),
                     };
                 })
             })?)
             .await?;
 
+        let blockchain = Arc::clone(&self.blockchain);
+        scheduler
+            .add(Job::new_async("0 * * * * *", move |_, _| {
+                let blockchain = Arc::clone(&blockchain);
+                Box::pin(async move {
+                    match blockchain.prune() {
+                        Ok(report) if report.bodies > 0 => info!(
+                            "Pruned {} block bodies and {} headers, reclaimed {} bytes",
+                            report.bodies, report.headers, report.reclaimed
+                        ),
+                        Err(e) => warn!("Cannot prune storage: {}", e),
+                        _ => {}
+                    }
+                })
+            })?)
+            .await?;
+
         scheduler.start().await?;
 
+        let mut reputation_interval = tokio::time::interval(Duration::from_secs(60));
+        let mut redial_interval = tokio::time::interval(Duration::from_secs(1));
+        let mut discovery_interval = tokio::time::interval(DISCOVERY_INTERVAL);
+
         loop {
             select! {
                 event = self.swarm.select_next_some() => {
-                    self.handle_swarm_event(event).await;
+                    let span = Self::request_span(&event);
+                    self.handle_swarm_event(event).instrument(span).await;
+                },
+                _ = reputation_interval.tick() => self.refresh_reputation(),
+                _ = discovery_interval.tick() => self.discover_peers(),
+                _ = redial_interval.tick() => {
+                    self.redial_bootstrap_nodes();
+                    self.drive_sync();
+                    self.orphans.expire(Instant::now());
+                    self.connect_orphans();
+                },
+                Some(tx) = rpc_rx.recv() => {
+                    let json = serde_json::to_string(&tx)?;
+                    if let Err(e) = self
+                        .swarm
+                        .behaviour_mut()
+                        .gossipsub
+                        .publish(self.tx_topic.clone(), json)
+                    {
+                        warn!("Error publishing to swarm: {:?}", e);
+                    }
                 },
                 event = validator_rx.recv() => {
                     if let Some(block) = event {
                         let json = serde_json::to_string(&block)?;
-                        println!("{}", serde_json::to_string_pretty(&json)?);
+                        info!(idx = block.idx, hash = %block.hash_str(), "Block created");
                         if let Err(e) = self.swarm.behaviour_mut().gossipsub.publish(
                             self.block_topic.clone(),
                             json
                         ) {
-                            println!("Error publishing block: {:?}", e);
+                            warn!("Error publishing block: {:?}", e);
                         }
                     }
                 }
@@ -189,17 +407,21 @@ impl Node {
     async fn handle_swarm_event(&mut self, event: SwarmEvent<NodeBehaviourEvent>) {
         match event {
             SwarmEvent::Behaviour(NodeBehaviourEvent::Mdns(mdns::Event::Discovered(list))) => {
-                for (peer_id, _multiaddr) in list {
-                    println!("mDNS discovered a new peer: {}", peer_id);
+                for (peer_id, multiaddr) in list {
+                    debug!("mDNS discovered a new peer: {}", peer_id);
                     self.swarm
                         .behaviour_mut()
                         .gossipsub
                         .add_explicit_peer(&peer_id);
+                    self.swarm
+                        .behaviour_mut()
+                        .kademlia
+                        .add_address(&peer_id, multiaddr);
                 }
             }
             SwarmEvent::Behaviour(NodeBehaviourEvent::Mdns(mdns::Event::Expired(list))) => {
                 for (peer_id, _multiaddr) in list {
-                    println!("mDNS discovered peer has expired: {}", peer_id);
+                    debug!("mDNS discovered peer has expired: {}", peer_id);
                     self.swarm
                         .behaviour_mut()
                         .gossipsub
@@ -207,26 +429,157 @@ impl Node {
                 }
             }
             SwarmEvent::Behaviour(NodeBehaviourEvent::Gossipsub(gossipsub::Event::Message {
-                propagation_source: _,
-                message_id: _,
+                propagation_source,
+                message_id,
                 message,
             })) => {
-                self.process_topic_message(&message).await;
+                let acceptance = self.process_topic_message(&message);
+                let topic = if message.topic == self.block_topic.hash() {
+                    BLOCK_TOPIC
+                } else {
+                    TX_TOPIC
+                };
+                let result = match acceptance {
+                    MessageAcceptance::Accept => "accept",
+                    MessageAcceptance::Reject => "reject",
+                    MessageAcceptance::Ignore => "ignore",
+                };
+                self.metrics.gossip(topic, result);
+                match acceptance {
+                    MessageAcceptance::Reject => {
+                        self.punish(propagation_source, Offence::InvalidGossip)
+                    }
+                    MessageAcceptance::Accept if message.topic == self.block_topic.hash() => {
+                        self.connect_orphans()
+                    }
+                    MessageAcceptance::Ignore if message.topic == self.block_topic.hash() => {
+                        self.handle_unconnected_block(propagation_source, &message.data)
+                    }
+                    _ => {}
+                }
+                self.swarm
+                    .behaviour_mut()
+                    .gossipsub
+                    .report_message_validation_result(&message_id, &propagation_source, acceptance);
             }
             SwarmEvent::Behaviour(NodeBehaviourEvent::Gossipsub(
                 gossipsub::Event::Subscribed { peer_id, topic },
             )) => {
-                println!("subscribed {} to {}", peer_id, topic);
+                debug!("subscribed {} to {}", peer_id, topic);
+            }
+            SwarmEvent::NewListenAddr { address, .. } => info!("Node started: {}", address),
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Identify(identify::Event::Received {
+                peer_id,
+                info,
+                ..
+            })) => {
+                let addresses: Vec<Multiaddr> = info
+                    .listen_addrs
+                    .iter()
+                    .filter(|address| discovery::routable(address))
+                    .cloned()
+                    .collect();
+                let bootstrap = self.swarm.behaviour_mut().kademlia.kbuckets().count() == 0;
+                for address in &addresses {
+                    self.swarm
+                        .behaviour_mut()
+                        .kademlia
+                        .add_address(&peer_id, address.clone());
+                }
+                if bootstrap && !addresses.is_empty() {
+                    self.bootstrap_kademlia();
+                }
+                let unsupported: Vec<&str> = protocol::NODE_PROTOCOLS
+                    .iter()
+                    .filter(|protocol| {
+                        !protocol
+                            .stream_protocols()
+                            .iter()
+                            .any(|supported| info.protocols.contains(supported))
+                    })
+                    .map(|protocol| protocol.name)
+                    .collect();
+                if !unsupported.is_empty() {
+                    warn!(
+                        "Peer {} has no common version of: {}",
+                        peer_id,
+                        unsupported.join(", ")
+                    );
+                }
+                let addresses: Vec<String> = addresses.iter().map(|a| a.to_string()).collect();
+                if let Err(e) = self
+                    .blockchain
+                    .peer_storage()
+                    .save(&peer_id.to_string(), &addresses)
+                {
+                    warn!("Cannot save peer {}: {}", peer_id, e);
+                }
+            }
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Kademlia(
+                kad::Event::OutboundQueryProgressed {
+                    result: kad::QueryResult::GetClosestPeers(Ok(result)),
+                    ..
+                },
+            )) => {
+                for peer in result.peers {
+                    self.dial_discovered_peer(peer);
+                }
+            }
+            SwarmEvent::ConnectionEstablished {
+                peer_id,
+                connection_id,
+                num_established,
+                ..
+            } => {
+                self.redial.connected(connection_id);
+                self.report_peers();
+                if num_established.get() == 1 {
+                    self.send_status(peer_id);
+                    let request_id = self
+                        .swarm
+                        .behaviour_mut()
+                        .mempool
+                        .send_request(&peer_id, MempoolRequest::Inventory);
+                    self.track_request(wire::MEMPOOL.name, request_id);
+                }
+            }
+            SwarmEvent::OutgoingConnectionError {
+                connection_id,
+                error,
+                ..
+            } => {
+                if let Some(backoff) = self.redial.disconnected(connection_id, Instant::now()) {
+                    warn!(
+                        "Cannot reach bootstrap node: {}, retrying in {:?}",
+                        error, backoff
+                    );
+                }
+            }
+            SwarmEvent::ConnectionClosed {
+                peer_id,
+                connection_id,
+                num_established,
+                ..
+            } => {
+                self.report_peers();
+                if num_established == 0 {
+                    self.sync.remove_peer(&peer_id);
+                }
+                if let Some(backoff) = self.redial.disconnected(connection_id, Instant::now()) {
+                    info!("Bootstrap node disconnected, redialing in {:?}", backoff);
+                }
             }
-            SwarmEvent::NewListenAddr { address, .. } => println!("Node started: {}", address),
             SwarmEvent::Behaviour(NodeBehaviourEvent::Nonce(
-                request_response::Event::Message { message, .. },
+                request_response::Event::Message { peer, message, .. },
             )) => match message {
                 request_response::Message::Request {
                     request_id: _,
                     request,
                     channel,
                 } => {
+                    if !self.allow_request(peer) {
+                        return;
+                    }
                     let nonce = self.blockchain.nonce(request.address.clone());
                     self.swarm
                         .behaviour_mut()
@@ -244,13 +597,44 @@ impl Node {
                     response: _,
                 } => {}
             },
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Account(
+                request_response::Event::Message {
+                    peer,
+                    message:
+                        request_response::Message::Request {
+                            request, channel, ..
+                        },
+                    ..
+                },
+            )) => {
+                if !self.allow_request(peer) {
+                    return;
+                }
+                match self.blockchain.account(request.address) {
+                    Ok(account) => {
+                        if let Err(e) = self
+                            .swarm
+                            .behaviour_mut()
+                            .account
+                            .send_response(channel, account)
+                        {
+                            warn!("Error sending response: {:?}", e);
+                        }
+                    }
+                    Err(e) => warn!("Cannot read account: {}", e),
+                }
+            }
             SwarmEvent::Behaviour(NodeBehaviourEvent::Tx(request_response::Event::Message {
+                peer,
                 message,
                 ..
             })) => match message {
                 request_response::Message::Request {
                     request, channel, ..
                 } => {
+                    if !self.allow_request(peer) {
+                        return;
+                    }
                     let response = match self.blockchain.add_tx(&request) {
                         Ok(_) => {
                             let json = serde_json::to_string(&request).unwrap();
@@ -260,7 +644,7 @@ impl Node {
                                 .gossipsub
                                 .publish(self.tx_topic.clone(), json)
                             {
-                                println!("Error publishing to swarm: {:?}", e);
+                                warn!("Error publishing to swarm: {:?}", e);
                             }
                             TxResponse { error: None }
                         }
@@ -274,52 +658,882 @@ impl Node {
                         .tx
                         .send_response(channel, response)
                     {
-                        println!("Error sending response: {:?}", e);
+                        warn!("Error sending response: {:?}", e);
                     }
                 }
                 _ => {}
             },
             SwarmEvent::Behaviour(NodeBehaviourEvent::FindBlock(
-                request_response::Event::Message { message, .. },
+                request_response::Event::Message { peer, message, .. },
+            )) => match message {
+                request_response::Message::Request {
+                    request, channel, ..
+                } => {
+                    let cost = match request {
+                        BlockRequest::AccountTxs { .. } | BlockRequest::AccountProof { .. } => {
+                            PROOF_REQUEST_COST
+                        }
+                        _ => 1.0,
+                    };
+                    if !self.allow_requests(peer, cost) {
+                        return;
+                    }
+                    let response = match request {
+                        BlockRequest::ByIdx { idx } => self
+                            .blockchain
+                            .find_block_by_idx(idx)
+                            .map(BlockResponse::Block),
+                        BlockRequest::ByHash { hash } => self
+                            .blockchain
+                            .find_block_by_hash(hash)
+                            .map(BlockResponse::Block),
+                        BlockRequest::Headers { from, count } => self
+                            .blockchain
+                            .find_headers(from, count.min(MAX_RANGE))
+                            .map(BlockResponse::Headers),
+                        BlockRequest::TxByHash { hash } => {
+                            self.blockchain.find_tx_proof(hash).map(BlockResponse::Tx)
+                        }
+                        BlockRequest::Receipt { hash } => {
+                            self.blockchain.receipt(hash).map(BlockResponse::Receipt)
+                        }
+                        BlockRequest::AccountTxs { address } => {
+                            unavailable(self.blockchain.find_account_proofs(address).map(Some))
+                                .map(BlockResponse::AccountTxs)
+                        }
+                        BlockRequest::AccountProof { address, idx } => {
+                            unavailable(self.blockchain.find_account_proof(address, idx))
+                                .map(BlockResponse::AccountProof)
+                        }
+                    };
+                    match response {
+                        Ok(response) => {
+                            if let Err(e) = self
+                                .swarm
+                                .behaviour_mut()
+                                .find_block
+                                .send_response(channel, response)
+                            {
+                                warn!("Error sending response: {:?}", e);
+                            }
+                        }
+                        Err(e) => warn!("Cannot read block: {}", e),
+                    }
+                }
+                request_response::Message::Response {
+                    request_id,
+                    response,
+                } => match response {
+                    BlockResponse::Block(block) => {
+                        self.handle_orphan_parent(peer, request_id, block)
+                    }
+                    BlockResponse::Headers(headers) => {
+                        if !headers.iter().all(|header| self.valid_header(header)) {
+                            self.punish(peer, Offence::BadBlockResponse);
+                        } else if let Some(from) = self.fork_requests.remove(&request_id) {
+                            self.handle_fork_headers(peer, request_id, from, headers);
+                        }
+                    }
+                    BlockResponse::Tx(proof) => {
+                        let valid =
+                            |proof: &TxProof| proof.verify() && self.valid_header(&proof.header);
+                        if proof.is_some_and(|proof| !valid(&proof)) {
+                            self.punish(peer, Offence::BadBlockResponse);
+                        }
+                    }
+                    BlockResponse::Receipt(_) => {}
+                    BlockResponse::AccountTxs(proofs) => {
+                        if proofs.is_some_and(|proofs| !proofs.iter().all(|proof| proof.verify())) {
+                            self.punish(peer, Offence::BadBlockResponse);
+                        }
+                    }
+                    BlockResponse::AccountProof(proof) => {
+                        let valid = |proof: &AccountProof| {
+                            proof.verify() && self.valid_header(&proof.header)
+                        };
+                        if proof.is_some_and(|proof| !valid(&proof)) {
+                            self.punish(peer, Offence::BadBlockResponse);
+                        }
+                    }
+                },
+            },
+            SwarmEvent::Behaviour(NodeBehaviourEvent::FindBlock(
+                request_response::Event::OutboundFailure {
+                    peer,
+                    request_id,
+                    error,
+                    ..
+                },
+            )) => {
+                self.finish_request(wire::BLOCK.name, request_id, Self::failure(&error));
+                self.fork_requests.remove(&request_id);
+                if let Some(hash) = self.orphan_requests.remove(&request_id) {
+                    warn!("Cannot get block {} from {}: {}", hash, peer, error);
+                }
+                if let request_response::OutboundFailure::Timeout = error {
+                    self.punish(peer, Offence::Timeout);
+                }
+            }
+            SwarmEvent::Behaviour(
+                NodeBehaviourEvent::Nonce(request_response::Event::OutboundFailure {
+                    peer,
+                    error: request_response::OutboundFailure::Timeout,
+                    ..
+                })
+                | NodeBehaviourEvent::Tx(request_response::Event::OutboundFailure {
+                    peer,
+                    error: request_response::OutboundFailure::Timeout,
+                    ..
+                }),
+            ) => self.punish(peer, Offence::Timeout),
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Blocks(
+                request_response::Event::Message { peer, message, .. },
+            )) => match message {
+                request_response::Message::Request {
+                    request, channel, ..
+                } => {
+                    if !self.allow_request(peer) {
+                        return;
+                    }
+                    let count = request.count.min(MAX_RANGE);
+                    match self.blockchain.find_blocks(request.from, count) {
+                        Ok(blocks) => {
+                            if let Err(e) = self
+                                .swarm
+                                .behaviour_mut()
+                                .blocks
+                                .send_response(channel, BlocksResponse { blocks })
+                            {
+                                warn!("Error sending response: {:?}", e);
+                            }
+                        }
+                        Err(e) => warn!("Cannot read blocks: {}", e),
+                    }
+                }
+                request_response::Message::Response {
+                    request_id,
+                    response,
+                } => self.handle_blocks_response(peer, request_id, response.blocks),
+            },
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Blocks(
+                request_response::Event::OutboundFailure {
+                    peer,
+                    request_id,
+                    error,
+                    ..
+                },
+            )) => {
+                self.finish_request(wire::BLOCKS.name, request_id, Self::failure(&error));
+                if let Some(from) = self.sync_requests.remove(&request_id) {
+                    warn!("Cannot get blocks from {} at {}: {}", peer, from, error);
+                    self.sync.on_failure(from);
+                }
+                if let request_response::OutboundFailure::Timeout = error {
+                    self.punish(peer, Offence::Timeout);
+                }
+            }
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Mempool(
+                request_response::Event::Message { peer, message, .. },
+            )) => match message {
+                request_response::Message::Request {
+                    request, channel, ..
+                } => {
+                    if !self.allow_request(peer) {
+                        return;
+                    }
+                    let response = match request {
+                        MempoolRequest::Inventory => {
+                            mempool::inventory(&self.blockchain).map(MempoolResponse::Inventory)
+                        }
+                        MempoolRequest::Txs { hashes } => {
+                            mempool::find_pending(&self.blockchain, &hashes)
+                                .map(MempoolResponse::Txs)
+                        }
+                    };
+                    match response {
+                        Ok(response) => {
+                            if let Err(e) = self
+                                .swarm
+                                .behaviour_mut()
+                                .mempool
+                                .send_response(channel, response)
+                            {
+                                warn!("Error sending response: {:?}", e);
+                            }
+                        }
+                        Err(e) => warn!("Cannot read mempool: {}", e),
+                    }
+                }
+                request_response::Message::Response {
+                    request_id,
+                    response,
+                } => {
+                    self.finish_request(wire::MEMPOOL.name, request_id, "ok");
+                    self.handle_mempool_response(peer, response)
+                }
+            },
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Mempool(
+                request_response::Event::OutboundFailure {
+                    peer,
+                    request_id,
+                    error,
+                    ..
+                },
+            )) => {
+                self.finish_request(wire::MEMPOOL.name, request_id, Self::failure(&error));
+                if let request_response::OutboundFailure::Timeout = error {
+                    self.punish(peer, Offence::Timeout);
+                }
+            }
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Status(
+                request_response::Event::Message { peer, message, .. },
             )) => match message {
                 request_response::Message::Request {
                     request, channel, ..
                 } => {
-                    if let Ok(block) = self.blockchain.find_block_by_idx(request.idx) {
-                        let response = BlockResponse { block };
+                    if !self.allow_request(peer) {
+                        return;
+                    }
+                    match self.local_status() {
+                        Ok(status) => {
+                            if let Err(e) = self
+                                .swarm
+                                .behaviour_mut()
+                                .status
+                                .send_response(channel, status)
+                            {
+                                warn!("Error sending response: {:?}", e);
+                            }
+                        }
+                        Err(e) => warn!("Cannot read status: {}", e),
+                    }
+                    self.handle_status(peer, request);
+                }
+                request_response::Message::Response {
+                    request_id,
+                    response,
+                } => {
+                    self.finish_request(wire::STATUS.name, request_id, "ok");
+                    self.handle_status(peer, response)
+                }
+            },
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Status(
+                request_response::Event::OutboundFailure {
+                    request_id, error, ..
+                },
+            )) => self.finish_request(wire::STATUS.name, request_id, Self::failure(&error)),
+            SwarmEvent::Behaviour(NodeBehaviourEvent::Snapshot(
+                request_response::Event::Message {
+                    peer,
+                    message:
+                        request_response::Message::Request {
+                            request, channel, ..
+                        },
+                    ..
+                },
+            )) => {
+                if !self.allow_request(peer) {
+                    return;
+                }
+                let response = match request {
+                    SnapshotRequest::Manifest => SnapshotResponse::Manifest(
+                        self.blockchain.find_snapshot().ok().flatten().map(Box::new),
+                    ),
+                    SnapshotRequest::Chunk { height, idx } => SnapshotResponse::Chunk(
+                        self.blockchain
+                            .find_snapshot_chunk(height, idx)
+                            .ok()
+                            .flatten(),
+                    ),
+                    SnapshotRequest::Validators { idx } => {
+                        SnapshotResponse::Validators(self.blockchain.validators(idx).ok().flatten())
+                    }
+                };
+                if let Err(e) = self
+                    .swarm
+                    .behaviour_mut()
+                    .snapshot
+                    .send_response(channel, response)
+                {
+                    warn!("Error sending response: {:?}", e);
+                }
+            }
+            SwarmEvent::Behaviour(NodeBehaviourEvent::History(
+                request_response::Event::Message {
+                    peer,
+                    message: request_response::Message::Request { channel, .. },
+                    ..
+                },
+            )) => {
+                if !self.allow_request(peer) {
+                    return;
+                }
+                match self.blockchain.history() {
+                    Ok(history) => {
                         if let Err(e) = self
                             .swarm
                             .behaviour_mut()
-                            .find_block
-                            .send_response(channel, response)
+                            .history
+                            .send_response(channel, history)
                         {
-                            println!("Error sending response: {:?}", e);
+                            warn!("Error sending response: {:?}", e);
                         }
                     }
+                    Err(e) => warn!("Cannot read history: {}", e),
                 }
-                _ => {}
-            },
+            }
             _ => {}
         }
     }
 
-    async fn process_topic_message(&self, message: &gossipsub::Message) {
-        let topic = message.topic.clone();
-        if topic == self.tx_topic.hash() {
-            let tx: Tx =
-                serde_json::from_str(String::from_utf8(message.clone().data).unwrap().as_str())
-                    .unwrap();
-            if let Err(e) = self.blockchain.add_tx(&tx) {
-                println!("Error sending message: {:?}", e);
+    fn request_span(event: &SwarmEvent<NodeBehaviourEvent>) -> tracing::Span {
+        let SwarmEvent::Behaviour(event) = event else {
+            return tracing::Span::none();
+        };
+        let (protocol, peer) = match event {
+            NodeBehaviourEvent::Nonce(event) => (wire::NONCE.name, request_peer(event)),
+            NodeBehaviourEvent::Tx(event) => (wire::TX.name, request_peer(event)),
+            NodeBehaviourEvent::FindBlock(event) => (wire::BLOCK.name, request_peer(event)),
+            NodeBehaviourEvent::Blocks(event) => (wire::BLOCKS.name, request_peer(event)),
+            NodeBehaviourEvent::Snapshot(event) => (wire::SNAPSHOT.name, request_peer(event)),
+            NodeBehaviourEvent::History(event) => (wire::HISTORY.name, request_peer(event)),
+            NodeBehaviourEvent::Status(event) => (wire::STATUS.name, request_peer(event)),
+            NodeBehaviourEvent::Mempool(event) => (wire::MEMPOOL.name, request_peer(event)),
+            NodeBehaviourEvent::Account(event) => (wire::ACCOUNT.name, request_peer(event)),
+            _ => return tracing::Span::none(),
+        };
+        tracing::info_span!("peer_request", protocol, %peer)
+    }
+
+    fn report_peers(&self) {
+        let peers = self.swarm.network_info().num_peers();
+        self.metrics.peers(peers);
+    }
+
+    fn track_request(
+        &mut self,
+        protocol: &'static str,
+        request_id: request_response::OutboundRequestId,
+    ) {
+        self.requests.insert((protocol, request_id), Instant::now());
+    }
+
+    fn finish_request(
+        &mut self,
+        protocol: &'static str,
+        request_id: request_response::OutboundRequestId,
+        outcome: &str,
+    ) {
+        if let Some(sent) = self.requests.remove(&(protocol, request_id)) {
+            self.metrics.request(protocol, outcome, sent.elapsed());
+        }
+    }
+
+    fn failure(error: &request_response::OutboundFailure) -> &'static str {
+        match error {
+            request_response::OutboundFailure::Timeout => "timeout",
+            _ => "error",
+        }
+    }
+
+    fn local_status(&self) -> Result<Status, std::io::Error> {
+        let latest_block = self.blockchain.find_latest()?;
+        Ok(Status {
+            version: String::from(STATUS_VERSION),
+            chain_id: self.chain_id.clone(),
+            genesis_hash: self.blockchain.genesis_hash()?,
+            head_height: latest_block.idx,
+            head_hash: latest_block.hash_str(),
+            finalized_height: self.blockchain.finalized_height()?,
+            earliest_body: self.blockchain.history()?.earliest_body,
+        })
+    }
+
+    fn send_status(&mut self, peer: PeerId) {
+        match self.local_status() {
+            Ok(status) => {
+                let request_id = self
+                    .swarm
+                    .behaviour_mut()
+                    .status
+                    .send_request(&peer, status);
+                self.track_request(wire::STATUS.name, request_id);
             }
-        } else if topic == self.block_topic.hash() {
-            let block: Block =
-                serde_json::from_str(String::from_utf8(message.clone().data).unwrap().as_str())
-                    .unwrap();
-            match self.blockchain.add_block(&block) {
-                Err(e) => println!("Error adding block: {:?}", e),
-                _ => {}
+            Err(e) => warn!("Cannot read status: {}", e),
+        }
+    }
+
+    fn handle_status(&mut self, peer: PeerId, status: Status) {
+        let local = match self.local_status() {
+            Ok(local) => local,
+            Err(e) => {
+                warn!("Cannot read status: {}", e);
+                return;
+            }
+        };
+        match local.compare(&status) {
+            PeerStatus::Incompatible => {
+                warn!(
+                    "Peer {} is on chain {} with genesis {} and version {}, banning",
+                    peer, status.chain_id, status.genesis_hash, status.version
+                );
+                if self.reputation.ban(peer) {
+                    self.block_peer(peer);
+                }
+            }
+            PeerStatus::Pruned => info!(
+                "Peer {} keeps blocks from {} only, enable fast sync",
+                peer, status.earliest_body
+            ),
+            PeerStatus::Ahead => {
+                info!("Peer {} is at block {}, syncing", peer, status.head_height);
+                self.sync.update_peer(peer, status.head_height);
+                self.drive_sync();
+            }
+            PeerStatus::Behind => self.sync.update_peer(peer, status.head_height),
+        }
+    }
+
+    fn drive_sync(&mut self) {
+        let (mut local_head, mut local_hash) = match self.blockchain.find_latest() {
+            Ok(latest_block) => (latest_block.idx, latest_block.hash_str()),
+            Err(e) => {
+                warn!("Cannot read latest block: {}", e);
+                return;
+            }
+        };
+        self.metrics.sync_target(self.sync.target().max(local_head));
+        if !self.sync.is_syncing(local_head) {
+            return;
+        }
+        let mut ready = self.sync.ready(local_head).into_iter();
+        while let Some((peer, block)) = ready.next() {
+            if block.parent_hash != local_hash {
+                info!("Peer {} is on another fork at block {}", peer, block.idx);
+                self.sync.fork(block.idx, peer, ready.collect());
+                self.find_fork_point(peer, local_head);
+                break;
+            }
+            if let Err(e) = self.blockchain.add_block(&block) {
+                warn!("Cannot add block {} from {}: {}", block.idx, peer, e);
+                if e.kind() == std::io::ErrorKind::InvalidInput {
+                    self.punish(peer, Offence::BadBlockResponse);
+                }
+                self.sync.reject(block.idx, peer, ready.collect());
+                break;
+            }
+            local_head = block.idx;
+            local_hash = block.hash_str();
+        }
+        if !self.sync.is_syncing(local_head) {
+            info!("Synced to block {}", local_head);
+            return;
+        }
+        for (peer, range) in self.sync.next_requests(local_head) {
+            let request_id = self.swarm.behaviour_mut().blocks.send_request(
+                &peer,
+                BlocksRequest {
+                    from: range.from,
+                    count: range.count,
+                },
+            );
+            self.sync_requests.insert(request_id, range.from);
+            self.track_request(wire::BLOCKS.name, request_id);
+        }
+    }
+
+    // Headers from the finalized height on show where the peer's chain leaves ours.
+    fn find_fork_point(&mut self, peer: PeerId, local_head: u64) {
+        let from = match self.blockchain.finalized_height() {
+            Ok(finalized_height) => finalized_height,
+            Err(e) => {
+                warn!("Cannot read finalized height: {}", e);
+                return;
+            }
+        };
+        let count = (local_head - from + 1).min(MAX_RANGE);
+        let request_id = self
+            .swarm
+            .behaviour_mut()
+            .find_block
+            .send_request(&peer, BlockRequest::Headers { from, count });
+        self.fork_requests.insert(request_id, from);
+        self.track_request(wire::BLOCK.name, request_id);
+    }
+
+    fn handle_fork_headers(
+        &mut self,
+        peer: PeerId,
+        request_id: request_response::OutboundRequestId,
+        from: u64,
+        headers: Vec<Block>,
+    ) {
+        self.finish_request(wire::BLOCK.name, request_id, "ok");
+        if headers.is_empty() {
+            info!("Peer {} has no headers from block {}", peer, from);
+            return;
+        }
+        let local = match self.blockchain.find_headers(from, headers.len() as u64) {
+            Ok(local) => local,
+            Err(e) => {
+                warn!("Cannot read headers: {}", e);
+                return;
+            }
+        };
+        let ancestor = headers
+            .iter()
+            .zip(&local)
+            .take_while(|(remote, local)| {
+                remote.idx == local.idx && remote.hash_str() == local.hash_str()
+            })
+            .last()
+            .map(|(remote, _)| remote.idx);
+        match ancestor {
+            Some(ancestor) => info!(
+                "Peer {} forked after block {}, keeping the local chain",
+                peer, ancestor
+            ),
+            None => {
+                warn!(
+                    "Peer {} conflicts with finalized block {}, banning",
+                    peer, from
+                );
+                if self.reputation.ban(peer) {
+                    self.block_peer(peer);
+                }
+            }
+        }
+    }
+
+    fn handle_blocks_response(
+        &mut self,
+        peer: PeerId,
+        request_id: request_response::OutboundRequestId,
+        blocks: Vec<Block>,
+    ) {
+        self.finish_request(wire::BLOCKS.name, request_id, "ok");
+        let Some(from) = self.sync_requests.remove(&request_id) else {
+            return;
+        };
+        if !blocks.iter().all(validation::well_formed) {
+            self.punish(peer, Offence::BadBlockResponse);
+            self.sync.on_failure(from);
+            return;
+        }
+        self.sync.on_response(from, blocks);
+        self.drive_sync();
+    }
+
+    fn handle_unconnected_block(&mut self, peer: PeerId, data: &[u8]) {
+        let Ok(block) = serde_json::from_slice::<Block>(data) else {
+            return;
+        };
+        let latest_idx = match self.blockchain.find_latest() {
+            Ok(latest_block) => latest_block.idx,
+            Err(e) => {
+                warn!("Cannot read latest block: {}", e);
+                return;
+            }
+        };
+        if block.idx <= latest_idx + 1
+            || self.orphans.contains(&block.hash_str())
+            || !validation::well_formed(&block)
+        {
+            return;
+        }
+        if block.idx - latest_idx > MAX_ORPHANS as u64 {
+            self.send_status(peer);
+            return;
+        }
+        self.add_orphan(peer, block);
+    }
+
+    fn add_orphan(&mut self, peer: PeerId, block: Block) {
+        let hash = block.hash_str();
+        if !self.orphans.insert(block, peer, Instant::now()) {
+            return;
+        }
+        let Some(parent_hash) = self.orphans.missing_parent(&hash) else {
+            return;
+        };
+        if self.orphan_requests.values().any(|h| *h == parent_hash) {
+            return;
+        }
+        debug!(
+            "Requesting block {} from {}, {} orphans pooled",
+            parent_hash,
+            peer,
+            self.orphans.len()
+        );
+        let request_id = self.swarm.behaviour_mut().find_block.send_request(
+            &peer,
+            BlockRequest::ByHash {
+                hash: parent_hash.clone(),
+            },
+        );
+        self.orphan_requests.insert(request_id, parent_hash);
+        self.track_request(wire::BLOCK.name, request_id);
+    }
+
+    fn handle_orphan_parent(
+        &mut self,
+        peer: PeerId,
+        request_id: request_response::OutboundRequestId,
+        block: Option<Block>,
+    ) {
+        self.finish_request(wire::BLOCK.name, request_id, "ok");
+        let Some(hash) = self.orphan_requests.remove(&request_id) else {
+            return;
+        };
+        let Some(block) = block else {
+            info!("Peer {} does not have block {}", peer, hash);
+            return;
+        };
+        if block.hash_str() != hash || !validation::well_formed(&block) {
+            self.punish(peer, Offence::BadBlockResponse);
+            return;
+        }
+        let latest_block = match self.blockchain.find_latest() {
+            Ok(latest_block) => latest_block,
+            Err(e) => {
+                warn!("Cannot read latest block: {}", e);
+                return;
+            }
+        };
+        if block.idx <= latest_block.idx {
+            return;
+        }
+        if block.idx == latest_block.idx + 1 && block.parent_hash == latest_block.hash_str() {
+            if let Err(e) = self.blockchain.add_block(&block) {
+                warn!("Cannot add block {} from {}: {}", block.idx, peer, e);
+                if e.kind() == std::io::ErrorKind::InvalidInput {
+                    self.punish(peer, Offence::BadBlockResponse);
+                }
+                return;
+            }
+            self.connect_orphans();
+        } else {
+            self.add_orphan(peer, block);
+        }
+    }
+
+    fn connect_orphans(&mut self) {
+        loop {
+            let latest_hash = match self.blockchain.find_latest() {
+                Ok(latest_block) => latest_block.hash_str(),
+                Err(e) => {
+                    warn!("Cannot read latest block: {}", e);
+                    return;
+                }
+            };
+            let mut connected = false;
+            for (peer, block) in self.orphans.take_children(&latest_hash) {
+                if connected {
+                    continue;
+                }
+                match self.blockchain.add_block(&block) {
+                    Ok(_) => {
+                        info!("Connected orphan block {}", block.idx);
+                        connected = true;
+                    }
+                    Err(e) => {
+                        warn!("Cannot add orphan block {} from {}: {}", block.idx, peer, e);
+                        if e.kind() == std::io::ErrorKind::InvalidInput {
+                            self.punish(peer, Offence::InvalidGossip);
+                        }
+                    }
+                }
+            }
+            if !connected {
+                return;
+            }
+        }
+    }
+
+    fn handle_mempool_response(&mut self, peer: PeerId, response: MempoolResponse) {
+        match response {
+            MempoolResponse::Inventory(hashes) => {
+                match mempool::missing(&self.blockchain, &hashes) {
+                    Ok(hashes) if hashes.is_empty() => {}
+                    Ok(hashes) => {
+                        let request_id = self
+                            .swarm
+                            .behaviour_mut()
+                            .mempool
+                            .send_request(&peer, MempoolRequest::Txs { hashes });
+                        self.track_request(wire::MEMPOOL.name, request_id);
+                    }
+                    Err(e) => warn!("Cannot read mempool: {}", e),
+                }
+            }
+            MempoolResponse::Txs(txs) => {
+                let applied = mempool::apply(&self.blockchain, txs);
+                if applied.added > 0 {
+                    info!("Added {} pending txs from {}", applied.added, peer);
+                }
+                if applied.invalid > 0 {
+                    self.punish(peer, Offence::BadTxResponse);
+                }
+            }
+        }
+    }
+
+    fn dial_known_peers(&mut self) -> Result<(), std::io::Error> {
+        for (peer_id, addresses) in self.blockchain.peer_storage().find_all()? {
+            let Ok(peer_id) = peer_id.parse::<PeerId>() else {
+                continue;
+            };
+            let addresses: Vec<Multiaddr> =
+                addresses.iter().filter_map(|a| a.parse().ok()).collect();
+            for address in &addresses {
+                self.swarm
+                    .behaviour_mut()
+                    .kademlia
+                    .add_address(&peer_id, address.clone());
+            }
+            let opts = DialOpts::peer_id(peer_id).addresses(addresses).build();
+            if let Err(e) = self.swarm.dial(opts) {
+                warn!("Cannot dial known peer {}: {}", peer_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    fn bootstrap_kademlia(&mut self) {
+        if let Err(e) = self.swarm.behaviour_mut().kademlia.bootstrap() {
+            debug!("Cannot bootstrap Kademlia: {}", e);
+        }
+    }
+
+    fn discover_peers(&mut self) {
+        self.swarm
+            .behaviour_mut()
+            .kademlia
+            .get_closest_peers(PeerId::random());
+    }
+
+    fn dial_discovered_peer(&mut self, peer: kad::PeerInfo) {
+        if self.swarm.is_connected(&peer.peer_id) || self.reputation.is_banned(&peer.peer_id) {
+            return;
+        }
+        let addresses: Vec<Multiaddr> =
+            peer.addrs.into_iter().filter(discovery::routable).collect();
+        if addresses.is_empty() {
+            return;
+        }
+        debug!("Kademlia discovered a new peer: {}", peer.peer_id);
+        let opts = DialOpts::peer_id(peer.peer_id).addresses(addresses).build();
+        if let Err(e) = self.swarm.dial(opts) {
+            debug!("Cannot dial discovered peer {}: {}", peer.peer_id, e);
+        }
+    }
+
+    fn redial_bootstrap_nodes(&mut self) {
+        let now = Instant::now();
+        for address in self.redial.due(now) {
+            let opts = DialOpts::unknown_peer_id().address(address.clone()).build();
+            let connection_id = opts.connection_id();
+            self.redial.dialing(connection_id, address.clone());
+            if let Err(e) = self.swarm.dial(opts) {
+                warn!("Cannot dial bootstrap node {}: {}", address, e);
+                self.redial.disconnected(connection_id, now);
             }
         }
     }
+
+    fn valid_header(&self, header: &Block) -> bool {
+        match self.blockchain.genesis_hash() {
+            Ok(genesis_hash) => validation::valid_header(header, &genesis_hash),
+            Err(e) => {
+                warn!("Cannot read genesis block: {}", e);
+                false
+            }
+        }
+    }
+
+    fn allow_request(&mut self, peer: PeerId) -> bool {
+        self.allow_requests(peer, 1.0)
+    }
+
+    fn allow_requests(&mut self, peer: PeerId, cost: f64) -> bool {
+        if self.reputation.is_banned(&peer) {
+            return false;
+        }
+        if self.reputation.allow_request(peer, cost) {
+            return true;
+        }
+        self.punish(peer, Offence::RequestFlood);
+        false
+    }
+
+    fn punish(&mut self, peer: PeerId, offence: Offence) {
+        let banned = self.reputation.penalize(peer, offence);
+        let score = self.reputation.score(&peer);
+        self.swarm
+            .behaviour_mut()
+            .gossipsub
+            .set_application_score(&peer, score);
+        if banned {
+            warn!("Peer {} banned, score: {}", peer, score);
+            self.block_peer(peer);
+        }
+    }
+
+    fn block_peer(&mut self, peer: PeerId) {
+        let behaviour = self.swarm.behaviour_mut();
+        behaviour.gossipsub.blacklist_peer(&peer);
+        behaviour.kademlia.remove_peer(&peer);
+        behaviour.blocked_peers.block_peer(peer);
+        if let Err(e) = self.blockchain.peer_storage().delete(&peer.to_string()) {
+            warn!("Cannot delete peer {}: {}", peer, e);
+        }
+    }
+
+    fn refresh_reputation(&mut self) {
+        self.reputation.decay();
+        let swarm = &self.swarm;
+        self.reputation.evict(|peer| swarm.is_connected(peer));
+        for (peer, score) in self.reputation.scores() {
+            self.swarm
+                .behaviour_mut()
+                .gossipsub
+                .set_application_score(&peer, score);
+        }
+        for peer in self.reputation.expire_bans() {
+            info!("Peer {} unbanned", peer);
+            let behaviour = self.swarm.behaviour_mut();
+            behaviour.gossipsub.remove_blacklisted_peer(&peer);
+            behaviour.blocked_peers.unblock_peer(peer);
+        }
+    }
+
+    fn process_topic_message(&self, message: &gossipsub::Message) -> MessageAcceptance {
+        if message.topic == self.tx_topic.hash() {
+            validation::validate_tx(&self.blockchain, &message.data)
+        } else if message.topic == self.block_topic.hash() {
+            validation::validate_block(&self.blockchain, &message.data)
+        } else {
+            MessageAcceptance::Ignore
+        }
+    }
+}
+
+// Pruned or oversized account data is answered as unavailable instead of not at all.
+fn unavailable<T>(result: Result<Option<T>, std::io::Error>) -> Result<Option<T>, std::io::Error> {
+    match result {
+        Err(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::InvalidInput) => {
+            debug!("Account data unavailable: {}", e);
+            Ok(None)
+        }
+        result => result,
+    }
+}
+
+fn request_peer<Request, Response>(event: &request_response::Event<Request, Response>) -> &PeerId {
+    match event {
+        request_response::Event::Message { peer, .. }
+        | request_response::Event::OutboundFailure { peer, .. }
+        | request_response::Event::InboundFailure { peer, .. }
+        | request_response::Event::ResponseSent { peer, .. } => peer,
+    }
 }
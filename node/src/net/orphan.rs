@@ -0,0 +1,92 @@
+use chain::block::Block;
+use libp2p::PeerId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub const MAX_ORPHANS: usize = 64;
+pub const MAX_ORPHAN_AGE: Duration = Duration::from_secs(300);
+
+struct Orphan {
+    block: Block,
+    peer: PeerId,
+    received: Instant,
+}
+
+pub struct OrphanPool {
+    orphans: HashMap<String, Orphan>,
+    max_size: usize,
+    max_age: Duration,
+}
+
+impl OrphanPool {
+    pub fn new(max_size: usize, max_age: Duration) -> Self {
+        Self {
+            orphans: HashMap::new(),
+            max_size,
+            max_age,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.orphans.len()
+    }
+
+    pub fn contains(&self, hash: &str) -> bool {
+        self.orphans.contains_key(hash)
+    }
+
+    pub fn insert(&mut self, block: Block, peer: PeerId, now: Instant) -> bool {
+        let hash = block.hash_str();
+        if self.orphans.contains_key(&hash) || self.max_size == 0 {
+            return false;
+        }
+        if self.orphans.len() >= self.max_size
+            && let Some(oldest) = self
+                .orphans
+                .iter()
+                .min_by_key(|(_, orphan)| orphan.received)
+                .map(|(hash, _)| hash.clone())
+        {
+            self.orphans.remove(&oldest);
+        }
+        self.orphans.insert(
+            hash,
+            Orphan {
+                block,
+                peer,
+                received: now,
+            },
+        );
+        true
+    }
+
+    pub fn missing_parent(&self, hash: &str) -> Option<String> {
+        let mut orphan = self.orphans.get(hash)?;
+        while let Some(parent) = self.orphans.get(&orphan.block.parent_hash) {
+            orphan = parent;
+        }
+        Some(orphan.block.parent_hash.clone())
+    }
+
+    pub fn take_children(&mut self, parent_hash: &str) -> Vec<(PeerId, Block)> {
+        let hashes: Vec<String> = self
+            .orphans
+            .iter()
+            .filter(|(_, orphan)| orphan.block.parent_hash == parent_hash)
+            .map(|(hash, _)| hash.clone())
+            .collect();
+        hashes
+            .iter()
+            .filter_map(|hash| self.orphans.remove(hash))
+            .map(|orphan| (orphan.peer, orphan.block))
+            .collect()
+    }
+
+    pub fn expire(&mut self, now: Instant) -> usize {
+        let before = self.orphans.len();
+        let max_age = self.max_age;
+        self.orphans
+            .retain(|_, orphan| now.duration_since(orphan.received) < max_age);
+        before - self.orphans.len()
+    }
+}
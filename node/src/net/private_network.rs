@@ -0,0 +1,10 @@
+use libp2p::identity::{Keypair, secp256k1};
+use wallet::wallet::Wallet;
+
+pub use network::private_network::{allowed_peers, read_psk, transport};
+
+pub fn keypair(wallet: &Wallet) -> Result<Keypair, std::io::Error> {
+    let secret = secp256k1::SecretKey::try_from_bytes(wallet.secret())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    Ok(Keypair::from(secp256k1::Keypair::from(secret)))
+}
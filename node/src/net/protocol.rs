@@ -0,0 +1,8 @@
+pub use chain::wire::{
+    ACCOUNT, BLOCK, BLOCKS, HISTORY, MEMPOOL, NONCE, Protocol, SNAPSHOT, STATUS, TX,
+};
+pub use network::protocol::ProtocolExt;
+
+pub const NODE_PROTOCOLS: [&Protocol; 9] = [
+    &NONCE, &TX, &BLOCK, &BLOCKS, &SNAPSHOT, &HISTORY, &STATUS, &MEMPOOL, &ACCOUNT,
+];
@@ -0,0 +1,147 @@
+use libp2p::PeerId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub const BAN_THRESHOLD: f64 = -100.0;
+const SCORE_DECAY: f64 = 5.0;
+// Account proofs and histories are built from the whole state or many blocks.
+pub const PROOF_REQUEST_COST: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offence {
+    InvalidGossip,
+    BadBlockResponse,
+    BadTxResponse,
+    Timeout,
+    RequestFlood,
+}
+
+impl Offence {
+    pub fn penalty(&self) -> f64 {
+        match self {
+            Offence::InvalidGossip => 25.0,
+            Offence::BadBlockResponse => 25.0,
+            Offence::BadTxResponse => 25.0,
+            Offence::Timeout => 5.0,
+            Offence::RequestFlood => 10.0,
+        }
+    }
+}
+
+struct PeerState {
+    score: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+pub type Clock = Box<dyn Fn() -> Instant + Send>;
+
+pub struct Reputation {
+    peers: HashMap<PeerId, PeerState>,
+    banned: HashMap<PeerId, Instant>,
+    ban_duration: Duration,
+    request_rate: f64,
+    clock: Clock,
+}
+
+impl Reputation {
+    pub fn new(ban_duration: Duration, request_rate: u32) -> Self {
+        Self::with_clock(ban_duration, request_rate, Box::new(Instant::now))
+    }
+
+    pub fn with_clock(ban_duration: Duration, request_rate: u32, clock: Clock) -> Self {
+        Self {
+            peers: HashMap::new(),
+            banned: HashMap::new(),
+            ban_duration,
+            request_rate: request_rate as f64,
+            clock,
+        }
+    }
+
+    pub fn score(&self, peer: &PeerId) -> f64 {
+        self.peers.get(peer).map_or(0.0, |state| state.score)
+    }
+
+    pub fn scores(&self) -> Vec<(PeerId, f64)> {
+        self.peers
+            .iter()
+            .map(|(peer, state)| (*peer, state.score))
+            .collect()
+    }
+
+    pub fn is_banned(&self, peer: &PeerId) -> bool {
+        self.banned.contains_key(peer)
+    }
+
+    pub fn penalize(&mut self, peer: PeerId, offence: Offence) -> bool {
+        let now = (self.clock)();
+        let state = self.state(peer, now);
+        state.score -= offence.penalty();
+        if state.score > BAN_THRESHOLD || self.banned.contains_key(&peer) {
+            return false;
+        }
+        self.banned.insert(peer, now + self.ban_duration);
+        true
+    }
+
+    pub fn ban(&mut self, peer: PeerId) -> bool {
+        let until = (self.clock)() + self.ban_duration;
+        self.banned.insert(peer, until).is_none()
+    }
+
+    pub fn allow_request(&mut self, peer: PeerId, cost: f64) -> bool {
+        let request_rate = self.request_rate;
+        let cost = cost.min(request_rate);
+        let now = (self.clock)();
+        let state = self.state(peer, now);
+        let elapsed = now.duration_since(state.refilled).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * request_rate).min(request_rate);
+        state.refilled = now;
+        if state.tokens < cost {
+            return false;
+        }
+        state.tokens -= cost;
+        true
+    }
+
+    pub fn decay(&mut self) {
+        for state in self.peers.values_mut() {
+            state.score = (state.score + SCORE_DECAY).min(0.0);
+        }
+    }
+
+    // Disconnected peers back at a neutral score carry no state worth keeping.
+    pub fn evict(&mut self, connected: impl Fn(&PeerId) -> bool) -> usize {
+        let before = self.peers.len();
+        let banned = &self.banned;
+        self.peers.retain(|peer, state| {
+            state.score < 0.0 || connected(peer) || banned.contains_key(peer)
+        });
+        before - self.peers.len()
+    }
+
+    pub fn expire_bans(&mut self) -> Vec<PeerId> {
+        let now = (self.clock)();
+        let expired: Vec<PeerId> = self
+            .banned
+            .iter()
+            .filter(|(_, until)| **until <= now)
+            .map(|(peer, _)| *peer)
+            .collect();
+        for peer in &expired {
+            self.banned.remove(peer);
+            self.peers.remove(peer);
+        }
+        expired
+    }
+
+    fn state(&mut self, peer: PeerId, now: Instant) -> &mut PeerState {
+        let request_rate = self.request_rate;
+        self.peers.entry(peer).or_insert_with(|| PeerState {
+            score: 0.0,
+            tokens: request_rate,
+            refilled: now,
+        })
+    }
+}
@@ -0,0 +1,30 @@
+use chain::wire::{STATUS, Status};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerStatus {
+    Incompatible,
+    Behind,
+    Ahead,
+    Pruned,
+}
+
+pub trait StatusExt {
+    fn compare(&self, remote: &Status) -> PeerStatus;
+}
+
+impl StatusExt for Status {
+    fn compare(&self, remote: &Status) -> PeerStatus {
+        if !STATUS.compatible(&remote.version)
+            || remote.chain_id != self.chain_id
+            || remote.genesis_hash != self.genesis_hash
+        {
+            PeerStatus::Incompatible
+        } else if remote.head_height <= self.head_height {
+            PeerStatus::Behind
+        } else if remote.earliest_body > self.head_height + 1 {
+            PeerStatus::Pruned
+        } else {
+            PeerStatus::Ahead
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use chain::block::Block;
+use libp2p::PeerId;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+pub const MAX_RANGE: u64 = 64;
+const MAX_IN_FLIGHT_PER_PEER: usize = 2;
+const MAX_BUFFERED: u64 = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    pub from: u64,
+    pub count: u64,
+}
+
+struct Retry {
+    range: Range,
+    failed_by: Option<PeerId>,
+}
+
+#[derive(Default)]
+pub struct SyncManager {
+    heads: HashMap<PeerId, u64>,
+    next_idx: u64,
+    in_flight: BTreeMap<u64, (PeerId, Range)>,
+    retries: VecDeque<Retry>,
+    buffered: BTreeMap<u64, (PeerId, Block)>,
+}
+
+impl SyncManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update_peer(&mut self, peer: PeerId, head: u64) {
+        self.heads.insert(peer, head);
+    }
+
+    pub fn remove_peer(&mut self, peer: &PeerId) {
+        self.heads.remove(peer);
+        let lost: Vec<u64> = self
+            .in_flight
+            .iter()
+            .filter(|(_, (owner, _))| owner == peer)
+            .map(|(from, _)| *from)
+            .collect();
+        for from in lost {
+            self.on_failure(from);
+        }
+    }
+
+    pub fn target(&self) -> u64 {
+        self.heads.values().copied().max().unwrap_or_default()
+    }
+
+    pub fn is_syncing(&self, local_head: u64) -> bool {
+        self.target() > local_head
+    }
+
+    pub fn next_requests(&mut self, local_head: u64) -> Vec<(PeerId, Range)> {
+        let mut requests = Vec::new();
+        self.next_idx = self.next_idx.max(local_head + 1);
+        for retry in self.retries.iter_mut() {
+            let end = retry.range.from + retry.range.count;
+            retry.range.from = retry.range.from.max(local_head + 1);
+            retry.range.count = end.saturating_sub(retry.range.from);
+        }
+        self.retries.retain(|retry| retry.range.count > 0);
+        let limit = local_head + MAX_BUFFERED;
+        loop {
+            let (range, failed_by) = match self.retries.front() {
+                Some(retry) => (retry.range, retry.failed_by),
+                None => {
+                    let target = self.target().min(limit);
+                    if self.next_idx > target {
+                        break;
+                    }
+                    let count = (target - self.next_idx + 1).min(MAX_RANGE);
+                    (
+                        Range {
+                            from: self.next_idx,
+                            count,
+                        },
+                        None,
+                    )
+                }
+            };
+            let Some(peer) = self.pick_peer(range, failed_by) else {
+                break;
+            };
+            if self.retries.pop_front().is_none() {
+                self.next_idx += range.count;
+            }
+            self.in_flight.insert(range.from, (peer, range));
+            requests.push((peer, range));
+        }
+        requests
+    }
+
+    pub fn on_response(&mut self, from: u64, blocks: Vec<Block>) {
+        let Some((peer, range)) = self.in_flight.remove(&from) else {
+            return;
+        };
+        let mut received = 0;
+        for block in blocks.into_iter().take(range.count as usize) {
+            if block.idx != from + received {
+                break;
+            }
+            self.buffered.insert(block.idx, (peer, block));
+            received += 1;
+        }
+        if received < range.count {
+            self.retries.push_back(Retry {
+                range: Range {
+                    from: from + received,
+                    count: range.count - received,
+                },
+                failed_by: Some(peer),
+            });
+        }
+    }
+
+    pub fn on_failure(&mut self, from: u64) {
+        if let Some((peer, range)) = self.in_flight.remove(&from) {
+            self.retries.push_back(Retry {
+                range,
+                failed_by: Some(peer),
+            });
+        }
+    }
+
+    pub fn ready(&mut self, local_head: u64) -> Vec<(PeerId, Block)> {
+        self.buffered = self.buffered.split_off(&(local_head + 1));
+        let mut ready = Vec::new();
+        let mut next = local_head + 1;
+        while let Some(entry) = self.buffered.remove(&next) {
+            ready.push(entry);
+            next += 1;
+        }
+        ready
+    }
+
+    // Re-queues the rejected block and everything else the same peer delivered after it,
+    // ranges requested from other peers stay where they are.
+    pub fn reject(&mut self, idx: u64, peer: PeerId, rest: Vec<(PeerId, Block)>) {
+        let mut refetch = vec![idx];
+        for (owner, block) in rest {
+            if owner == peer {
+                refetch.push(block.idx);
+            } else {
+                self.buffered.insert(block.idx, (owner, block));
+            }
+        }
+        let buffered: Vec<u64> = self
+            .buffered
+            .range(idx..)
+            .filter(|(_, (owner, _))| *owner == peer)
+            .map(|(idx, _)| *idx)
+            .collect();
+        for idx in buffered {
+            self.buffered.remove(&idx);
+            refetch.push(idx);
+        }
+        refetch.sort_unstable();
+        let mut ranges: Vec<Range> = Vec::new();
+        for idx in refetch {
+            match ranges.last_mut() {
+                Some(range) if range.from + range.count == idx && range.count < MAX_RANGE => {
+                    range.count += 1
+                }
+                _ => ranges.push(Range {
+                    from: idx,
+                    count: 1,
+                }),
+            }
+        }
+        for range in ranges {
+            self.retries.push_back(Retry {
+                range,
+                failed_by: Some(peer),
+            });
+        }
+    }
+
+    // A peer on another fork is no source of blocks until it reports a new status.
+    pub fn fork(&mut self, idx: u64, peer: PeerId, rest: Vec<(PeerId, Block)>) {
+        self.reject(idx, peer, rest);
+        self.remove_peer(&peer);
+    }
+
+    fn pick_peer(&self, range: Range, failed_by: Option<PeerId>) -> Option<PeerId> {
+        let last = range.from + range.count - 1;
+        let mut candidates: Vec<(usize, PeerId)> = self
+            .heads
+            .iter()
+            .filter(|(_, head)| **head >= last)
+            .map(|(peer, _)| {
+                let load = self
+                    .in_flight
+                    .values()
+                    .filter(|(owner, _)| owner == peer)
+                    .count();
+                (load, *peer)
+            })
+            .filter(|(load, _)| *load < MAX_IN_FLIGHT_PER_PEER)
+            .collect();
+        candidates.sort_by_key(|(load, peer)| (Some(*peer) == failed_by, *load));
+        candidates.first().map(|(_, peer)| *peer)
+    }
+}
@@ -0,0 +1,75 @@
+use crate::blockchain::blockchain::Blockchain;
+use chain::block::Block;
+use chain::tx::Tx;
+use libp2p::gossipsub::MessageAcceptance;
+use tracing::{debug, warn};
+
+pub fn validate_tx(blockchain: &Blockchain, data: &[u8]) -> MessageAcceptance {
+    let Ok(tx) = serde_json::from_slice::<Tx>(data) else {
+        debug!("Rejected malformed tx message");
+        return MessageAcceptance::Reject;
+    };
+    match blockchain.nonce(tx.from()) {
+        Ok(nonce) if nonce + 1 == tx.nonce() => {}
+        Ok(_) => return MessageAcceptance::Ignore,
+        Err(e) => {
+            warn!("Cannot read nonce: {}", e);
+            return MessageAcceptance::Ignore;
+        }
+    }
+    match blockchain.add_tx(&tx) {
+        Ok(_) => MessageAcceptance::Accept,
+        Err(e) => {
+            debug!("Rejected tx {}: {}", tx.hash_str(), e);
+            acceptance(e)
+        }
+    }
+}
+
+pub fn validate_block(blockchain: &Blockchain, data: &[u8]) -> MessageAcceptance {
+    let Ok(block) = serde_json::from_slice::<Block>(data) else {
+        debug!("Rejected malformed block message");
+        return MessageAcceptance::Reject;
+    };
+    match blockchain.find_latest() {
+        Ok(latest_block)
+            if block.idx == latest_block.idx + 1
+                && block.parent_hash == latest_block.hash_str() => {}
+        Ok(_) => return MessageAcceptance::Ignore,
+        Err(e) => {
+            warn!("Cannot read latest block: {}", e);
+            return MessageAcceptance::Ignore;
+        }
+    }
+    match blockchain.add_block(&block) {
+        Ok(_) => MessageAcceptance::Accept,
+        Err(e) => {
+            warn!("Rejected block {}: {}", block.idx, e);
+            acceptance(e)
+        }
+    }
+}
+
+pub fn well_formed(block: &Block) -> bool {
+    let txs = block.txs().unwrap_or_default();
+    block.merkle_root == hex::encode(Block::merkle_root(&txs))
+        && block.valid()
+        && txs.iter().all(|tx| tx.valid())
+}
+
+// The genesis block carries no signature, it is only valid as the local genesis.
+pub fn valid_header(header: &Block, genesis_hash: &str) -> bool {
+    if header.idx == 0 {
+        header.hash_str() == genesis_hash
+    } else {
+        header.valid()
+    }
+}
+
+fn acceptance(e: std::io::Error) -> MessageAcceptance {
+    if e.kind() == std::io::ErrorKind::InvalidInput {
+        MessageAcceptance::Reject
+    } else {
+        MessageAcceptance::Ignore
+    }
+}
@@ -0,0 +1,67 @@
+use crate::blockchain::archive;
+use crate::blockchain::blockchain::Blockchain;
+use crate::test::commons::{config, peer_config, wallet_with_balance};
+use chain::tx::Tx;
+use std::fs;
+use std::io::ErrorKind;
+use wallet::wallet::Wallet;
+
+fn export_import(compress: bool) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    let block = blockchain.create_block().unwrap();
+
+    let path = temp_dir.path().join("chain.bin");
+    let path = path.to_str().unwrap();
+    let exported = archive::export(&blockchain, path, compress).unwrap();
+    assert_eq!(exported, 2);
+
+    let import_dir = tempfile::tempdir().unwrap();
+    let import_config = peer_config(&config, import_dir.path());
+    let imported = Blockchain::new(Wallet::new(), &import_config).unwrap();
+    assert_eq!(archive::import(&imported, path).unwrap(), 1);
+    assert_eq!(imported.find_latest().unwrap().hash_str(), block.hash_str());
+    let found = imported.find_block_by_idx(1).unwrap().unwrap();
+    assert_eq!(found.txs.unwrap()[0].hash_str(), tx.hash_str());
+    assert_eq!(imported.nonce(wallet.address()).unwrap(), 2);
+
+    assert_eq!(archive::import(&imported, path).unwrap(), 0);
+}
+
+#[test]
+fn test_export_import() {
+    export_import(false);
+}
+
+#[test]
+fn test_export_import_compressed() {
+    export_import(true);
+}
+
+#[test]
+fn test_import_truncated_length() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    blockchain.create_block().unwrap();
+
+    let path = temp_dir.path().join("chain.bin");
+    let path = path.to_str().unwrap();
+    archive::export(&blockchain, path, false).unwrap();
+    let mut data = fs::read(path).unwrap();
+    data.extend_from_slice(&[0, 0]);
+    fs::write(path, data).unwrap();
+
+    let import_dir = tempfile::tempdir().unwrap();
+    let import_config = peer_config(&config, import_dir.path());
+    let imported = Blockchain::new(Wallet::new(), &import_config).unwrap();
+    let error = archive::import(&imported, path).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
@@ -0,0 +1,221 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::net::sync::MAX_RANGE;
+use crate::test::commons::{config, wallet_with_balance, with_options};
+use chain::state::State;
+use chain::system::STAKE_WALLET;
+use chain::tx::Tx;
+use serde_json::json;
+use std::io::ErrorKind;
+
+#[test]
+fn test_block_lookup_by_hash_and_headers() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    for nonce in 2..5 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        blockchain.create_block().unwrap();
+    }
+    let block = blockchain.find_block_by_idx(2).unwrap().unwrap();
+    assert_eq!(
+        blockchain.find_block_by_hash(block.hash_str()).unwrap(),
+        Some(block.clone())
+    );
+    assert!(
+        blockchain
+            .find_block_by_hash(String::from("unknown"))
+            .unwrap()
+            .is_none()
+    );
+
+    let headers = blockchain.find_headers(1, 10).unwrap();
+    assert_eq!(headers.len(), 3);
+    assert!(headers.iter().all(|header| header.txs.is_none()));
+    assert_eq!(headers[1].hash_str(), block.hash_str());
+}
+
+#[test]
+fn test_block_range_lookup() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    for nonce in 2..4 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        blockchain.create_block().unwrap();
+    }
+
+    let blocks = blockchain.find_blocks(1, MAX_RANGE).unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert!(blocks.iter().all(|block| block.txs.is_some()));
+    assert!(blockchain.find_blocks(u64::MAX, 1).unwrap().is_empty());
+    assert!(
+        blockchain
+            .find_headers(u64::MAX, MAX_RANGE)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_tx_inclusion_proof() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let mut txs = Vec::new();
+    for nonce in 2..7 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        txs.push(tx);
+    }
+    let pending = Tx::new(&wallet, String::from("to"), String::from("10"), 7).unwrap();
+    blockchain.create_block().unwrap();
+    blockchain.add_tx(&pending).unwrap();
+
+    for tx in &txs {
+        let proof = blockchain.find_tx_proof(tx.hash_str()).unwrap().unwrap();
+        assert_eq!(proof.tx.hash_str(), tx.hash_str());
+        assert_eq!(proof.header.idx, 1);
+        assert_eq!(proof.total, txs.len());
+        assert!(proof.verify());
+    }
+    assert!(
+        blockchain
+            .find_tx_proof(pending.hash_str())
+            .unwrap()
+            .is_none()
+    );
+
+    let mut proof = blockchain
+        .find_tx_proof(txs[0].hash_str())
+        .unwrap()
+        .unwrap();
+    proof.tx = pending;
+    assert!(!proof.verify());
+}
+
+fn history_state(blockchain: &Blockchain, address: &str) -> State {
+    let mut state = State::default();
+    for proof in blockchain.find_account_proofs(address.to_string()).unwrap() {
+        assert!(proof.verify());
+        state.apply(&proof.tx);
+    }
+    state
+}
+
+#[test]
+fn test_account_proofs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10.5"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    let tx = Tx::new(&wallet, String::from(STAKE_WALLET), String::from("100"), 3).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    let block = blockchain.create_block().unwrap();
+    let pending = Tx::new(&wallet, String::from("to"), String::from("1"), 4).unwrap();
+    blockchain.add_tx(&pending).unwrap();
+
+    let proofs = blockchain.find_account_proofs(wallet.address()).unwrap();
+    assert_eq!(proofs.len(), 4);
+    assert!(
+        proofs
+            .iter()
+            .all(|proof| proof.tx.hash_str() != pending.hash_str())
+    );
+    let account = history_state(&blockchain, &wallet.address()).account(&wallet.address());
+    assert_eq!(account.balance, "499889.5");
+    assert_eq!(account.stake, "500100");
+    assert_eq!(account.nonce, 3);
+
+    let proof = blockchain
+        .find_account_proof(wallet.address(), block.idx)
+        .unwrap()
+        .unwrap();
+    assert!(proof.verify_against(&block));
+    assert_eq!(proof.account, account);
+
+    let next = blockchain.create_block().unwrap();
+    let state = history_state(&blockchain, &wallet.address());
+    assert_eq!(
+        state.account(&wallet.address()),
+        blockchain.account(wallet.address()).unwrap()
+    );
+    let proof = blockchain
+        .find_account_proof(wallet.address(), next.idx)
+        .unwrap()
+        .unwrap();
+    assert!(proof.verify_against(&next));
+    assert!(!proof.verify_against(&block));
+    assert!(
+        blockchain
+            .find_account_proof(String::from("missing"), next.idx)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_account_proofs_at_kept_states() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let mut blocks = Vec::new();
+    for nonce in 2..4 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        blocks.push(blockchain.create_block().unwrap());
+    }
+
+    let error = blockchain
+        .find_account_proof(wallet.address(), blocks[0].idx)
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    blockchain.create_snapshot(blocks[0].idx).unwrap();
+    let proof = blockchain
+        .find_account_proof(wallet.address(), blocks[0].idx)
+        .unwrap()
+        .unwrap();
+    assert!(proof.verify_against(&blocks[0]));
+    assert_eq!(proof.account.nonce, 2);
+    let proof = blockchain
+        .find_account_proof(wallet.address(), blocks[1].idx)
+        .unwrap()
+        .unwrap();
+    assert!(proof.verify_against(&blocks[1]));
+    assert_eq!(proof.account.nonce, 3);
+}
+
+#[test]
+fn test_account_proofs_of_pruned_history() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let options = json!({ "storage_mode": "full", "keep_blocks": 1, "finality_depth": 1 });
+    let config = with_options(&config, options);
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    for nonce in 2..5 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        blockchain.create_block().unwrap();
+    }
+    blockchain.prune().unwrap();
+
+    let error = blockchain
+        .find_account_proofs(wallet.address())
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+    let head = blockchain.find_latest().unwrap();
+    let proof = blockchain
+        .find_account_proof(wallet.address(), head.idx)
+        .unwrap()
+        .unwrap();
+    assert!(proof.verify_against(&head));
+    assert_eq!(proof.account.nonce, 4);
+}
@@ -1,12 +1,12 @@
+use crate::blockchain::storage::block_storage::BlockStorage;
+use crate::blockchain::storage::db;
+use crate::test::commons::config;
+use chain::block::Block;
+use chain::tx::Tx;
 use std::fs;
 use std::sync::Arc;
 use tempfile::tempdir;
 use wallet::wallet::Wallet;
-use crate::blockchain::block::Block;
-use crate::blockchain::storage::block_storage::BlockStorage;
-use crate::blockchain::storage::db;
-use chain::tx::Tx;
-use crate::test::commons::config;
 
 #[test]
 fn test_block_save() {
@@ -21,10 +21,30 @@ pub fn config(path: &Path) -> Config {
         PORT,
         path.clone() + STORAGE_PATH,
         path.clone() + GENESIS_PATH,
-        vec![]
+        vec![],
     )
 }
 
+// Another node's config sharing the keystore and genesis of `config`.
+pub fn peer_config(config: &Config, path: &Path) -> Config {
+    Config::new(
+        config.keystore_path(),
+        config.validator(),
+        config.port(),
+        path.join(".storage").to_str().unwrap().to_string(),
+        config.genesis_path(),
+        vec![],
+    )
+}
+
+pub fn with_options(config: &Config, options: serde_json::Value) -> Config {
+    let mut value = serde_json::to_value(config).unwrap();
+    for (key, option) in options.as_object().unwrap() {
+        value[key] = option.clone();
+    }
+    serde_json::from_value(value).unwrap()
+}
+
 pub fn wallet(config: &Config) -> Wallet {
     let wallet = Wallet::new();
     wallet
@@ -0,0 +1,51 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::storage::db::Access;
+use crate::test::commons::{config, wallet_with_balance};
+use chain::tx::Tx;
+
+#[test]
+fn test_read_only_access() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let block = blockchain.create_block().unwrap();
+
+    let read_only = Blockchain::open(&config, &Access::ReadOnly).unwrap();
+    assert_eq!(
+        read_only.find_latest().unwrap().hash_str(),
+        block.hash_str()
+    );
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    assert!(read_only.add_tx(&tx).is_err());
+}
+
+#[test]
+fn test_secondary_catch_up() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+
+    let secondary_path = temp_dir.path().join(".secondary");
+    let access = Access::Secondary(secondary_path.to_str().unwrap().to_string());
+    let secondary = Blockchain::open(&config, &access).unwrap();
+    assert_eq!(secondary.find_latest().unwrap().idx, 0);
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    let block = blockchain.create_block().unwrap();
+    secondary.catch_up().unwrap();
+    assert_eq!(
+        secondary.find_latest().unwrap().hash_str(),
+        block.hash_str()
+    );
+    assert_eq!(secondary.nonce(wallet.address()).unwrap(), 2);
+}
+
+#[test]
+fn test_open_missing_storage() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    assert!(Blockchain::open(&config, &Access::ReadOnly).is_err());
+}
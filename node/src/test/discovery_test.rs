@@ -0,0 +1,88 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::net::discovery::{self, Redial};
+use crate::test::commons::{config, wallet_with_balance};
+use libp2p::Multiaddr;
+use libp2p::swarm::ConnectionId;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_redial_backoff() {
+    let address: Multiaddr = "/ip4/127.0.0.1/tcp/8089".parse().unwrap();
+    let mut redial = Redial::new(vec![address.clone()]);
+    let now = Instant::now();
+    assert_eq!(redial.due(now), vec![address.clone()]);
+    assert!(redial.due(now).is_empty());
+
+    let connection_id = ConnectionId::new_unchecked(1);
+    redial.dialing(connection_id, address.clone());
+    assert_eq!(
+        redial.disconnected(connection_id, now),
+        Some(Duration::from_secs(1))
+    );
+    assert!(redial.due(now).is_empty());
+    assert_eq!(
+        redial.due(now + Duration::from_secs(1)),
+        vec![address.clone()]
+    );
+
+    let connection_id = ConnectionId::new_unchecked(2);
+    redial.dialing(connection_id, address.clone());
+    assert_eq!(
+        redial.disconnected(connection_id, now),
+        Some(Duration::from_secs(2))
+    );
+    assert_eq!(
+        redial.due(now + Duration::from_secs(2)),
+        vec![address.clone()]
+    );
+
+    let connection_id = ConnectionId::new_unchecked(3);
+    redial.dialing(connection_id, address.clone());
+    redial.connected(connection_id);
+    assert_eq!(
+        redial.disconnected(connection_id, now),
+        Some(Duration::from_secs(1))
+    );
+    assert_eq!(
+        redial.disconnected(ConnectionId::new_unchecked(4), now),
+        None
+    );
+    assert_eq!(Redial::backoff(20), Duration::from_secs(300));
+}
+
+#[test]
+fn test_routable_addresses() {
+    let routable = |address: &str| discovery::routable(&address.parse().unwrap());
+    assert!(routable("/ip4/10.0.0.1/tcp/8089"));
+    assert!(routable("/ip6/2001:db8::1/udp/8089/quic-v1"));
+    assert!(!routable("/ip4/127.0.0.1/tcp/8089"));
+    assert!(!routable("/ip4/0.0.0.0/tcp/8089"));
+    assert!(!routable("/ip6/::1/tcp/8089"));
+}
+
+#[test]
+fn test_peer_storage() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let addresses = vec![String::from("/ip4/10.0.0.1/tcp/8089")];
+    blockchain
+        .peer_storage()
+        .save("peer-a", &addresses)
+        .unwrap();
+    blockchain.peer_storage().save("peer-b", &[]).unwrap();
+    drop(blockchain);
+
+    let blockchain = Blockchain::new(wallet, &config).unwrap();
+    let peers = blockchain.peer_storage().find_all().unwrap();
+    assert_eq!(
+        peers,
+        vec![
+            (String::from("peer-a"), addresses),
+            (String::from("peer-b"), vec![])
+        ]
+    );
+    blockchain.peer_storage().delete("peer-a").unwrap();
+    assert_eq!(blockchain.peer_storage().find_all().unwrap().len(), 1);
+}
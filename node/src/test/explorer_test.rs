@@ -0,0 +1,142 @@
+use crate::api::explorer::{Explorer, Pagination};
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::storage::db;
+use crate::blockchain::storage::stats_storage::StatsStorage;
+use crate::test::commons::{config, wallet_with_balance, with_options};
+use chain::receipt::TxStatus;
+use chain::tx::Tx;
+use serde_json::json;
+use std::sync::Arc;
+
+fn page(page: u64, limit: u64) -> Pagination {
+    Pagination { page, limit }
+}
+
+#[test]
+fn test_explorer_blocks_and_txs() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Arc::new(Blockchain::new(wallet.clone(), &config).unwrap());
+    let included = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    blockchain.add_tx(&included).unwrap();
+    let block = blockchain.create_block().unwrap();
+    let pending = Tx::new(&wallet, String::from("to"), String::from("10"), 3).unwrap();
+    blockchain.add_tx(&pending).unwrap();
+    let explorer = Explorer::new(Arc::clone(&blockchain));
+
+    let latest = explorer.blocks(page(0, 1)).unwrap();
+    assert_eq!(latest.total, 2);
+    assert_eq!(latest.items.len(), 1);
+    assert_eq!(latest.items[0].idx, 1);
+    assert_eq!(latest.items[0].tx_count, 1);
+    let genesis = explorer.blocks(page(1, 1)).unwrap();
+    assert_eq!(genesis.items[0].idx, 0);
+    assert_eq!(genesis.items[0].tx_count, 2);
+    assert!(explorer.blocks(page(5, 1)).unwrap().items.is_empty());
+    assert_eq!(explorer.blocks(page(0, 1000)).unwrap().limit, 100);
+
+    let details = explorer.block(String::from("1")).unwrap().unwrap();
+    assert_eq!(details.block.hash, block.hash_str());
+    assert_eq!(details.txs.unwrap().len(), 1);
+    assert_eq!(details.confirmations, 1);
+    let by_hash = explorer.block(block.hash_str()).unwrap().unwrap();
+    assert_eq!(by_hash.block.idx, 1);
+    assert!(explorer.block(String::from("missing")).unwrap().is_none());
+
+    let tx = explorer.tx(included.hash_str()).unwrap().unwrap();
+    assert_eq!(tx.receipt.status, TxStatus::Included);
+    assert_eq!(tx.receipt.block, Some(1));
+    assert_eq!(tx.receipt.index, Some(0));
+    assert_eq!(tx.confirmations, 1);
+    let tx = explorer.tx(pending.hash_str()).unwrap().unwrap();
+    assert_eq!(tx.receipt.status, TxStatus::Pending);
+    assert_eq!(tx.confirmations, 0);
+    assert!(explorer.tx(String::from("missing")).unwrap().is_none());
+}
+
+#[test]
+fn test_explorer_addresses_and_stats() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Arc::new(Blockchain::new(wallet.clone(), &config).unwrap());
+    for nonce in 2..4 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+    }
+    blockchain.create_block().unwrap();
+    let explorer = Explorer::new(Arc::clone(&blockchain));
+
+    let address = explorer.address(wallet.address(), page(0, 3)).unwrap();
+    assert_eq!(address.balance, "499980");
+    assert_eq!(address.stake, "500000");
+    assert_eq!(address.nonce, 3);
+    assert_eq!(address.txs.total, 4);
+    assert_eq!(address.txs.items.len(), 3);
+    let last = explorer.address(wallet.address(), page(1, 3)).unwrap();
+    assert_eq!(last.txs.total, 4);
+    assert_eq!(last.txs.items.len(), 1);
+    assert!(!address.txs.items.contains(&last.txs.items[0]));
+    assert!(
+        explorer
+            .address(wallet.address(), page(2, 3))
+            .unwrap()
+            .txs
+            .items
+            .is_empty()
+    );
+    let beyond = explorer
+        .address(wallet.address(), page(1_000_000_000, 3))
+        .unwrap();
+    assert_eq!(beyond.txs.total, 4);
+    assert!(beyond.txs.items.is_empty());
+    let recipient = explorer.address(String::from("to"), page(0, 20)).unwrap();
+    assert_eq!(recipient.balance, "20");
+    assert_eq!(recipient.txs.total, 2);
+
+    let validators = explorer.validators().unwrap();
+    assert_eq!(validators.len(), 1);
+    assert_eq!(validators[0].address, wallet.address());
+
+    let stats = explorer.stats().unwrap();
+    assert_eq!(stats.height, 1);
+    assert_eq!(stats.tx_count, 4);
+    assert_eq!(stats.pending_txs, 0);
+    assert_eq!(stats.total_supply, "1000000");
+    assert_eq!(stats.total_stake, "500000");
+    assert_eq!(stats.validators, 1);
+
+    blockchain.reindex().unwrap();
+    assert_eq!(explorer.stats().unwrap().tx_count, 4);
+}
+
+#[test]
+fn test_reindex_backfills_stats() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let options = json!({ "storage_mode": "full", "keep_blocks": 1, "finality_depth": 1 });
+    let config = with_options(&config, options);
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    for nonce in 2..6 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        blockchain.create_block().unwrap();
+    }
+    blockchain.create_snapshot(3).unwrap();
+    let tx_count = blockchain.tx_count().unwrap();
+    blockchain.prune().unwrap();
+    blockchain.reindex().unwrap();
+    assert_eq!(blockchain.tx_count().unwrap(), tx_count);
+    drop(blockchain);
+
+    StatsStorage::new(db::open(&config).unwrap())
+        .clear()
+        .unwrap();
+    let blockchain = Blockchain::new(wallet, &config).unwrap();
+    assert_eq!(blockchain.tx_count().unwrap(), 0);
+    blockchain.reindex().unwrap();
+    assert_eq!(blockchain.tx_count().unwrap(), tx_count);
+    assert_eq!(blockchain.block_tx_count(4).unwrap(), 1);
+}
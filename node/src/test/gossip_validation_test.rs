@@ -0,0 +1,123 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::net::validation::{valid_header, validate_block, validate_tx};
+use crate::test::commons::{config, wallet_with_balance, with_options};
+use chain::tx::Tx;
+use libp2p::gossipsub::MessageAcceptance;
+use serde_json::json;
+use wallet::wallet::Wallet;
+
+#[test]
+fn test_validate_tx_message() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+
+    assert!(matches!(
+        validate_tx(&blockchain, b"\xff\xfe"),
+        MessageAcceptance::Reject
+    ));
+    assert!(matches!(
+        validate_tx(&blockchain, b"{}"),
+        MessageAcceptance::Reject
+    ));
+
+    let mut tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    tx.amount = String::from("20");
+    let data = serde_json::to_vec(&tx).unwrap();
+    assert!(matches!(
+        validate_tx(&blockchain, &data),
+        MessageAcceptance::Reject
+    ));
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    let data = serde_json::to_vec(&tx).unwrap();
+    assert!(matches!(
+        validate_tx(&blockchain, &data),
+        MessageAcceptance::Accept
+    ));
+    assert!(matches!(
+        validate_tx(&blockchain, &data),
+        MessageAcceptance::Ignore
+    ));
+
+    let tx = Tx::new(&Wallet::new(), String::from("to"), String::from("10"), 1).unwrap();
+    let data = serde_json::to_vec(&tx).unwrap();
+    assert!(matches!(
+        validate_tx(&blockchain, &data),
+        MessageAcceptance::Reject
+    ));
+}
+
+#[test]
+fn test_validate_block_message() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    blockchain.create_block().unwrap();
+    let block = blockchain.find_block_by_idx(1).unwrap().unwrap();
+
+    let peer_dir = tempfile::tempdir().unwrap();
+    let peer_storage = peer_dir.path().join(".storage");
+    let peer_config = with_options(
+        &config,
+        json!({ "storage_path": peer_storage.to_str().unwrap() }),
+    );
+    let peer = Blockchain::new(Wallet::new(), &peer_config).unwrap();
+
+    assert!(matches!(
+        validate_block(&peer, b"not a block"),
+        MessageAcceptance::Reject
+    ));
+
+    let mut tampered = block.clone();
+    tampered.txs = Some(vec![]);
+    let data = serde_json::to_vec(&tampered).unwrap();
+    assert!(matches!(
+        validate_block(&peer, &data),
+        MessageAcceptance::Reject
+    ));
+
+    let mut future = block.clone();
+    future.idx = 5;
+    let data = serde_json::to_vec(&future).unwrap();
+    assert!(matches!(
+        validate_block(&peer, &data),
+        MessageAcceptance::Ignore
+    ));
+
+    let data = serde_json::to_vec(&block).unwrap();
+    assert!(matches!(
+        validate_block(&peer, &data),
+        MessageAcceptance::Accept
+    ));
+    assert!(matches!(
+        validate_block(&peer, &data),
+        MessageAcceptance::Ignore
+    ));
+    assert_eq!(peer.find_latest().unwrap().hash_str(), block.hash_str());
+}
+
+#[test]
+fn test_genesis_header_matches_local_genesis() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    blockchain.proof_of_stake().unwrap();
+
+    let genesis_hash = blockchain.genesis_hash().unwrap();
+    let headers = blockchain.find_headers(0, 2).unwrap();
+    assert!(!headers[0].valid());
+    assert!(
+        headers
+            .iter()
+            .all(|header| valid_header(header, &genesis_hash))
+    );
+    let mut forged = headers[0].clone();
+    forged.timestamp += 1;
+    assert!(!valid_header(&forged, &genesis_hash));
+}
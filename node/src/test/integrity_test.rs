@@ -0,0 +1,127 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::storage::db;
+use crate::blockchain::storage::nonce_storage::NonceStorage;
+use crate::test::commons::{config, wallet_with_balance};
+use chain::tx::Tx;
+use std::sync::Arc;
+
+#[test]
+fn test_verify_and_reindex() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    for nonce in 2..5 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        blockchain.create_block().unwrap();
+    }
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 5).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    let report = blockchain.verify().unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert_eq!(report.blocks, 4);
+    let block = blockchain.find_block_by_idx(2).unwrap().unwrap();
+    drop(blockchain);
+
+    let storage = db::open(&config).unwrap();
+    storage.delete(format!("tx.{}", wallet.address())).unwrap();
+    storage.delete("tx.empty").unwrap();
+    storage
+        .delete(format!("block.{}", block.hash_str()))
+        .unwrap();
+    NonceStorage::new(Arc::clone(&storage))
+        .save(wallet.address(), 1)
+        .unwrap();
+    drop(storage);
+
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let report = blockchain.verify().unwrap();
+    assert_eq!(report.errors.len(), 7, "{:?}", report.errors);
+
+    let reindexed = blockchain.reindex().unwrap();
+    assert_eq!(reindexed.blocks, 4);
+    assert_eq!(reindexed.txs, 6);
+    let report = blockchain.verify().unwrap();
+    assert!(report.errors.is_empty(), "{:?}", report.errors);
+    assert_eq!(blockchain.nonce(wallet.address()).unwrap(), 5);
+    assert_eq!(
+        blockchain.find_latest().unwrap().hash_str(),
+        blockchain.find_block_by_idx(3).unwrap().unwrap().hash_str()
+    );
+    assert!(
+        blockchain
+            .create_block()
+            .unwrap()
+            .txs
+            .unwrap()
+            .contains(&tx)
+    );
+}
+
+#[test]
+fn test_verify_detects_missing_tx() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    blockchain.create_block().unwrap();
+    drop(blockchain);
+
+    let storage = db::open(&config).unwrap();
+    storage.delete(format!("tx.{}", tx.hash_str())).unwrap();
+    drop(storage);
+
+    let blockchain = Blockchain::new(wallet, &config).unwrap();
+    let report = blockchain.verify().unwrap();
+    assert_eq!(report.errors.len(), 2, "{:?}", report.errors);
+    assert!(report.errors[1].contains("merkle root"));
+}
+
+#[test]
+fn test_reindex_restores_tx_order() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    for nonce in 2..6 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+    }
+    let block = blockchain.create_block().unwrap();
+    drop(blockchain);
+
+    let storage = db::open(&config).unwrap();
+    storage.delete("tx.1").unwrap();
+    drop(storage);
+
+    let blockchain = Blockchain::new(wallet, &config).unwrap();
+    blockchain.reindex().unwrap();
+    let hashes = |txs: Vec<Tx>| txs.iter().map(|tx| tx.hash_str()).collect::<Vec<_>>();
+    let found = blockchain.find_block_by_idx(1).unwrap().unwrap();
+    assert_eq!(hashes(found.txs.unwrap()), hashes(block.txs.unwrap()));
+    assert!(blockchain.verify().unwrap().errors.is_empty());
+}
+
+#[test]
+fn test_reindex_keeps_corrupted_blocks() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    blockchain.create_block().unwrap();
+    drop(blockchain);
+
+    let storage = db::open(&config).unwrap();
+    storage.put("block.1", "corrupted").unwrap();
+    drop(storage);
+
+    let blockchain = Blockchain::new(wallet, &config).unwrap();
+    let error = blockchain.reindex().unwrap_err();
+    assert!(error.to_string().contains("block.1"));
+    drop(blockchain);
+    let storage = db::open(&config).unwrap();
+    assert_eq!(storage.get("block.1").unwrap(), Some(b"corrupted".to_vec()));
+}
@@ -0,0 +1,56 @@
+use crate::blockchain::config::LogFormat;
+use crate::cli::logging;
+use crate::test::commons::{config, with_options};
+use serde_json::{Value, json};
+use std::fs;
+
+#[test]
+fn test_json_log_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let log_file = temp_dir.path().join("logs/node.log");
+    let config = with_options(
+        &config(temp_dir.path()),
+        json!({
+            "log_level": "debug",
+            "log_format": "json",
+            "log_file": log_file.to_str().unwrap(),
+            "log_rotation": "never",
+        }),
+    );
+    assert_eq!(config.log_format(), LogFormat::Json);
+
+    let (subscriber, guard) =
+        logging::subscriber(&config, &config.log_level(), std::io::sink).unwrap();
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("peer_request", protocol = "blocks");
+        let _entered = span.enter();
+        tracing::debug!(idx = 7, "Block added");
+        tracing::trace!("Hidden");
+    });
+    drop(guard);
+
+    let logs = fs::read_to_string(log_file).unwrap();
+    let lines: Vec<Value> = logs
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["level"], "DEBUG");
+    assert_eq!(lines[0]["fields"]["message"], "Block added");
+    assert_eq!(lines[0]["fields"]["idx"], 7);
+    assert_eq!(lines[0]["span"]["name"], "peer_request");
+    assert_eq!(lines[0]["span"]["protocol"], "blocks");
+}
+
+#[test]
+fn test_invalid_log_level() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    assert_eq!(config.log_level(), "info");
+    assert_eq!(config.log_format(), LogFormat::Pretty);
+    let error = logging::subscriber(&config, "node=loud", std::io::sink).err();
+    assert_eq!(
+        error.map(|e| e.kind()),
+        Some(std::io::ErrorKind::InvalidInput)
+    );
+}
@@ -0,0 +1,69 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::net::mempool;
+use crate::test::commons::{config, wallet_with_balance};
+use chain::tx::Tx;
+use std::fs;
+
+#[test]
+fn test_mempool_sync_between_nodes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+
+    let other_dir = tempfile::tempdir().unwrap();
+    let other_config = crate::test::commons::config(other_dir.path());
+    fs::copy(config.genesis_path(), other_config.genesis_path()).unwrap();
+    let other = Blockchain::new(wallet.clone(), &other_config).unwrap();
+
+    let mut txs = Vec::new();
+    for nonce in 2..6 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        txs.push(tx);
+    }
+    other.add_tx(&txs[0]).unwrap();
+
+    let inventory = mempool::inventory(&blockchain).unwrap();
+    assert_eq!(inventory.len(), 4);
+    let missing = mempool::missing(&other, &inventory).unwrap();
+    assert_eq!(missing.len(), 3);
+    assert!(!missing.contains(&txs[0].hash_str()));
+
+    let mut found = mempool::find_pending(&blockchain, &missing).unwrap();
+    found.reverse();
+    let applied = mempool::apply(&other, found);
+    assert_eq!(
+        applied,
+        mempool::Applied {
+            added: 3,
+            invalid: 0
+        }
+    );
+    assert_eq!(other.nonce(wallet.address()).unwrap(), 5);
+    assert!(mempool::missing(&other, &inventory).unwrap().is_empty());
+}
+
+#[test]
+fn test_invalid_txs_counted() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    let mut forged = serde_json::to_value(&tx).unwrap();
+    forged["amount"] = serde_json::json!("20");
+    let forged: Tx = serde_json::from_value(forged).unwrap();
+    let stale = Tx::new(&wallet, String::from("to"), String::from("10"), 1).unwrap();
+
+    let applied = mempool::apply(&blockchain, vec![forged, stale]);
+    assert_eq!(
+        applied,
+        mempool::Applied {
+            added: 0,
+            invalid: 1
+        }
+    );
+    assert!(mempool::inventory(&blockchain).unwrap().is_empty());
+}
@@ -0,0 +1,54 @@
+use crate::api::metrics::Metrics;
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::hooks::ChainMetrics;
+use crate::test::commons::{config, wallet_with_balance};
+use chain::tx::Tx;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[test]
+fn test_render_metrics() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let metrics = Arc::new(Metrics::new());
+    let blockchain = Blockchain::new(wallet.clone(), &config)
+        .unwrap()
+        .with_metrics(metrics.clone());
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    assert!(blockchain.add_tx(&tx).is_err());
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10000000"), 3).unwrap();
+    assert!(blockchain.add_tx(&tx).is_err());
+
+    let text = metrics.render(&blockchain).unwrap();
+    assert!(text.contains("xhcg_head_height 0\n"));
+    assert!(text.contains("xhcg_mempool_size 1\n"));
+    assert!(text.contains("xhcg_txs_accepted_total 1\n"));
+    assert!(text.contains("xhcg_txs_rejected_total{reason=\"nonce\"} 1\n"));
+    assert!(text.contains("xhcg_txs_rejected_total{reason=\"balance\"} 1\n"));
+    assert!(text.contains("xhcg_rocksdb{property=\"rocksdb.estimate-num-keys\"}"));
+
+    blockchain.proof_of_stake().unwrap();
+    let text = metrics.render(&blockchain).unwrap();
+    assert!(text.contains("xhcg_blocks_produced_total 1\n"));
+    assert!(text.contains("xhcg_blocks_missed_total 0\n"));
+
+    metrics.block_missed();
+    metrics.peers(3);
+    metrics.gossip("block", "accept");
+    metrics.request("blocks", "ok", Duration::from_millis(20));
+    metrics.sync_target(7);
+    let text = metrics.render(&blockchain).unwrap();
+    assert!(text.contains("xhcg_head_height 1\n"));
+    assert!(text.contains("xhcg_mempool_size 0\n"));
+    assert!(text.contains("xhcg_blocks_produced_total 1\n"));
+    assert!(text.contains("xhcg_blocks_missed_total 1\n"));
+    assert!(text.contains("xhcg_peers 3\n"));
+    assert!(text.contains("xhcg_gossip_messages_total{topic=\"block\",result=\"accept\"} 1\n"));
+    assert!(
+        text.contains("xhcg_request_latency_seconds_count{protocol=\"blocks\",outcome=\"ok\"} 1\n")
+    );
+    assert!(text.contains("xhcg_sync_target_height 7\n"));
+}
@@ -1,11 +1,51 @@
 #[cfg(test)]
-mod block_storage;
+mod archive_test;
+#[cfg(test)]
+mod block_lookup_test;
 #[cfg(test)]
-mod block_test;
+mod block_storage;
 #[cfg(test)]
 mod blockchain_test;
 mod commons;
 #[cfg(test)]
+mod db_access_test;
+#[cfg(test)]
+mod discovery_test;
+#[cfg(test)]
+mod explorer_test;
+#[cfg(test)]
+mod gossip_validation_test;
+#[cfg(test)]
+mod integrity_test;
+#[cfg(test)]
+mod logging_test;
+#[cfg(test)]
+mod mempool_test;
+#[cfg(test)]
+mod metrics_test;
+#[cfg(test)]
 mod nonce_storage_test;
 #[cfg(test)]
+mod orphan_test;
+#[cfg(test)]
+mod private_network_test;
+#[cfg(test)]
+mod protocol_test;
+#[cfg(test)]
+mod pruning_test;
+#[cfg(test)]
+mod receipt_test;
+#[cfg(test)]
+mod reputation_test;
+#[cfg(test)]
+mod rpc_test;
+#[cfg(test)]
+mod snapshot_test;
+#[cfg(test)]
+mod status_test;
+#[cfg(test)]
+mod sync_test;
+#[cfg(test)]
 mod tx_storage_test;
+#[cfg(test)]
+mod ws_test;
@@ -0,0 +1,62 @@
+use crate::net::orphan::OrphanPool;
+use chain::block::Block;
+use chain::state::State;
+use libp2p::PeerId;
+use std::time::{Duration, Instant};
+use wallet::wallet::Wallet;
+
+fn chain(count: u64) -> Vec<Block> {
+    let wallet = Wallet::new();
+    let mut parent_hash = String::new();
+    let mut blocks = Vec::new();
+    for idx in 1..=count {
+        let block = Block::new(&wallet, idx, parent_hash, vec![], &State::default()).unwrap();
+        parent_hash = block.hash_str();
+        blocks.push(block);
+    }
+    blocks
+}
+
+#[test]
+fn test_orphans_connect_in_order() {
+    let blocks = chain(4);
+    let peer = PeerId::random();
+    let mut pool = OrphanPool::new(10, Duration::from_secs(60));
+    let now = Instant::now();
+    assert!(pool.insert(blocks[3].clone(), peer, now));
+    assert!(pool.insert(blocks[2].clone(), peer, now));
+    assert!(!pool.insert(blocks[2].clone(), peer, now));
+    assert_eq!(pool.len(), 2);
+
+    assert_eq!(
+        pool.missing_parent(&blocks[3].hash_str()),
+        Some(blocks[1].hash_str())
+    );
+    assert!(pool.take_children(&blocks[0].hash_str()).is_empty());
+
+    let children = pool.take_children(&blocks[1].hash_str());
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].0, peer);
+    assert_eq!(children[0].1.idx, 3);
+    let children = pool.take_children(&blocks[2].hash_str());
+    assert_eq!(children[0].1.idx, 4);
+    assert_eq!(pool.len(), 0);
+}
+
+#[test]
+fn test_pool_bounded_by_size_and_age() {
+    let blocks = chain(4);
+    let peer = PeerId::random();
+    let mut pool = OrphanPool::new(2, Duration::from_secs(60));
+    let now = Instant::now();
+    pool.insert(blocks[1].clone(), peer, now);
+    pool.insert(blocks[2].clone(), peer, now + Duration::from_secs(1));
+    pool.insert(blocks[3].clone(), peer, now + Duration::from_secs(2));
+    assert_eq!(pool.len(), 2);
+    assert!(!pool.contains(&blocks[1].hash_str()));
+
+    assert_eq!(pool.expire(now + Duration::from_secs(61)), 1);
+    assert!(pool.contains(&blocks[3].hash_str()));
+    assert_eq!(pool.expire(now + Duration::from_secs(62)), 1);
+    assert_eq!(pool.len(), 0);
+}
@@ -0,0 +1,59 @@
+use crate::net::private_network;
+use crate::test::commons::{config, with_options};
+use libp2p::pnet::PreSharedKey;
+use network::private_network::peer_id;
+use serde_json::json;
+use std::fs;
+use wallet::wallet::Wallet;
+
+#[test]
+fn test_peer_id_derived_from_wallet_address() {
+    let wallet = Wallet::new();
+    let keypair = private_network::keypair(&wallet).unwrap();
+    assert_eq!(
+        peer_id(&wallet.address()).unwrap(),
+        keypair.public().to_peer_id()
+    );
+    assert!(peer_id("not hex").is_err());
+    assert!(peer_id(&hex::encode([1u8; 33])).is_err());
+
+    let other = Wallet::new();
+    let allowed = private_network::allowed_peers(&[wallet.address(), other.address()]).unwrap();
+    assert_eq!(allowed.len(), 2);
+    assert!(allowed.contains(&keypair.public().to_peer_id()));
+}
+
+#[test]
+fn test_private_network_config() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    assert!(config.psk_path().is_none());
+    assert!(config.allowed_validators().is_empty());
+
+    let psk_path = temp_dir.path().join("swarm.key");
+    fs::write(
+        &psk_path,
+        format!(
+            "/key/swarm/psk/1.0.0/\n/base16/\n{}",
+            hex::encode([7u8; 32])
+        ),
+    )
+    .unwrap();
+    let wallet = Wallet::new();
+    let config = with_options(
+        &config,
+        json!({
+            "psk_path": psk_path.to_str().unwrap(),
+            "allowed_validators": [wallet.address()],
+        }),
+    );
+    let psk = private_network::read_psk(&config.psk_path().unwrap()).unwrap();
+    assert!(psk == PreSharedKey::new([7u8; 32]));
+    assert_eq!(
+        private_network::allowed_peers(&config.allowed_validators()).unwrap(),
+        vec![peer_id(&wallet.address()).unwrap()]
+    );
+
+    fs::write(&psk_path, "invalid").unwrap();
+    assert!(private_network::read_psk(psk_path.to_str().unwrap()).is_err());
+}
@@ -0,0 +1,83 @@
+use crate::net::protocol::{NODE_PROTOCOLS, Protocol, ProtocolExt, STATUS};
+use chain::wire::{BLOCK, BlockRequest, BlockResponse};
+use libp2p::StreamProtocol;
+use network::protocol::Versioned;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+
+const TEST: Protocol = Protocol {
+    name: "test",
+    versions: &["0.0.2", "0.0.1"],
+};
+
+#[test]
+fn test_stream_protocols_newest_first() {
+    assert_eq!(
+        TEST.stream_protocols(),
+        vec![
+            StreamProtocol::new("/test/0.0.2"),
+            StreamProtocol::new("/test/0.0.1")
+        ]
+    );
+    assert!(TEST.supports("0.0.1"));
+    assert!(!TEST.supports("0.0.3"));
+    assert!(TEST.compatible("0.0.3"));
+    assert!(!TEST.compatible("0.1.0"));
+}
+
+#[test]
+fn test_block_messages_follow_negotiated_version() {
+    let request = BlockRequest::ByIdx { idx: 7 };
+    let data = request.encode("0.0.1").unwrap();
+    assert_eq!(
+        serde_json::from_slice::<Value>(&data).unwrap(),
+        json!({"idx": 7})
+    );
+    assert!(matches!(
+        BlockRequest::decode("0.0.1", &data).unwrap(),
+        BlockRequest::ByIdx { idx: 7 }
+    ));
+    let data = request.encode("0.0.2").unwrap();
+    assert!(matches!(
+        BlockRequest::decode("0.0.2", &data).unwrap(),
+        BlockRequest::ByIdx { idx: 7 }
+    ));
+
+    let headers = BlockRequest::Headers { from: 0, count: 1 };
+    assert!(headers.encode("0.0.1").is_err());
+    assert!(headers.encode("0.0.2").is_ok());
+    let account = BlockRequest::AccountTxs {
+        address: String::from("wallet"),
+    };
+    assert!(account.encode("0.0.1").is_err());
+    let data = account.encode("0.0.2").unwrap();
+    assert!(BlockRequest::decode("0.0.1", &data).is_err());
+    let receipt = BlockRequest::Receipt {
+        hash: String::from("hash"),
+    };
+    assert!(receipt.encode("0.0.1").is_err());
+    assert!(receipt.encode("0.0.2").is_ok());
+    assert!(BLOCK.supports("0.0.1"));
+
+    let response = BlockResponse::Block(None);
+    let data = response.encode("0.0.1").unwrap();
+    assert_eq!(
+        serde_json::from_slice::<Value>(&data).unwrap(),
+        json!({"block": null})
+    );
+    assert!(matches!(
+        BlockResponse::decode("0.0.1", &data).unwrap(),
+        BlockResponse::Block(None)
+    ));
+    assert!(BlockResponse::Headers(vec![]).encode("0.0.1").is_err());
+}
+
+#[test]
+fn test_node_protocols_registered_once() {
+    let names: HashSet<&str> = NODE_PROTOCOLS.iter().map(|p| p.name).collect();
+    assert_eq!(names.len(), NODE_PROTOCOLS.len());
+    for protocol in NODE_PROTOCOLS {
+        assert_eq!(protocol.stream_protocols().len(), protocol.versions.len());
+    }
+    assert!(NODE_PROTOCOLS.iter().any(|p| p.name == STATUS.name));
+}
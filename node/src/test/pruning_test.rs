@@ -0,0 +1,108 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::config::StorageMode;
+use crate::test::commons::{config, wallet_with_balance, with_options};
+use chain::tx::Tx;
+use serde_json::json;
+use wallet::wallet::Wallet;
+
+fn build_chain(mode: &str) -> (tempfile::TempDir, Wallet, Blockchain) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let config = with_options(
+        &config,
+        json!({"storage_mode": mode, "keep_blocks": 1, "finality_depth": 1}),
+    );
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    for nonce in 2..6 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        blockchain.create_block().unwrap();
+    }
+    (temp_dir, wallet, blockchain)
+}
+
+#[test]
+fn test_archive_keeps_everything() {
+    let (_temp_dir, _, blockchain) = build_chain("archive");
+    let report = blockchain.prune().unwrap();
+    assert_eq!(report.bodies, 0);
+    assert!(blockchain.find_block_by_idx(1).unwrap().is_some());
+}
+
+#[test]
+fn test_full_prunes_bodies() {
+    let (_temp_dir, wallet, blockchain) = build_chain("full");
+    let balance = blockchain.balance(wallet.address()).unwrap();
+    let stake = blockchain
+        .wallet_stake(wallet.address())
+        .unwrap()
+        .unwrap()
+        .stake();
+
+    let report = blockchain.prune().unwrap();
+    assert_eq!(report.bodies, 4);
+    assert_eq!(report.headers, 0);
+    assert!(report.reclaimed > 0);
+
+    assert_eq!(blockchain.balance(wallet.address()).unwrap(), balance);
+    assert_eq!(
+        blockchain
+            .wallet_stake(wallet.address())
+            .unwrap()
+            .unwrap()
+            .stake(),
+        stake
+    );
+    assert!(blockchain.find_block_by_idx(3).unwrap().is_none());
+    assert_eq!(
+        blockchain
+            .find_block_by_idx(4)
+            .unwrap()
+            .unwrap()
+            .txs
+            .unwrap()
+            .len(),
+        1
+    );
+
+    let history = blockchain.history().unwrap();
+    assert_eq!(history.mode, StorageMode::Full);
+    assert_eq!(history.earliest_block, 0);
+    assert_eq!(history.earliest_body, 4);
+
+    assert_eq!(blockchain.prune().unwrap().bodies, 0);
+}
+
+#[test]
+fn test_pruned_removes_headers() {
+    let (_temp_dir, wallet, blockchain) = build_chain("pruned");
+    let balance = blockchain.balance(wallet.address()).unwrap();
+
+    let report = blockchain.prune().unwrap();
+    assert_eq!(report.bodies, 4);
+    assert_eq!(report.headers, 2);
+    assert_eq!(blockchain.history().unwrap().earliest_block, 3);
+    assert_eq!(blockchain.balance(wallet.address()).unwrap(), balance);
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 6).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    let block = blockchain.create_block().unwrap();
+    assert_eq!(block.idx, 5);
+    assert_eq!(
+        blockchain.balance(wallet.address()).unwrap(),
+        balance - bigdecimal::BigDecimal::from(10)
+    );
+}
+
+#[test]
+fn test_full_finds_empty_blocks_below_base() {
+    let (_temp_dir, _, blockchain) = build_chain("full");
+    blockchain.create_block().unwrap();
+    blockchain.create_block().unwrap();
+    assert_eq!(blockchain.prune().unwrap().bodies, 6);
+
+    assert!(blockchain.find_block_by_idx(4).unwrap().is_none());
+    let block = blockchain.find_block_by_idx(5).unwrap().unwrap();
+    assert_eq!(block.txs.unwrap().len(), 0);
+}
@@ -0,0 +1,100 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::test::commons::{config, peer_config, wallet_with_balance, with_options};
+use chain::block::Block;
+use chain::receipt::TxStatus;
+use chain::state::State;
+use chain::tx::Tx;
+use serde_json::json;
+use wallet::wallet::Wallet;
+
+#[test]
+fn test_receipt_lifecycle() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = with_options(&config(temp_dir.path()), json!({ "finality_depth": 1 }));
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+
+    let receipt = blockchain.receipt(tx.hash_str()).unwrap().unwrap();
+    assert_eq!(receipt.status, TxStatus::Pending);
+    assert_eq!(receipt.block, None);
+    assert!(!receipt.success);
+
+    blockchain.create_block().unwrap();
+    let receipt = blockchain.receipt(tx.hash_str()).unwrap().unwrap();
+    assert_eq!(receipt.status, TxStatus::Included);
+    assert_eq!(receipt.block, Some(1));
+    assert_eq!(receipt.index, Some(0));
+    assert!(receipt.success);
+
+    blockchain.create_block().unwrap();
+    let receipt = blockchain.receipt(tx.hash_str()).unwrap().unwrap();
+    assert_eq!(receipt.status, TxStatus::Finalized);
+    assert!(receipt.success);
+    assert!(
+        blockchain
+            .receipt(String::from("missing"))
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_receipt_replaced() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+
+    let peer_dir = tempfile::tempdir().unwrap();
+    let peer = Blockchain::new(Wallet::new(), &peer_config(&config, peer_dir.path())).unwrap();
+    let replaced = Tx::new(&wallet, String::from("other"), String::from("5"), 2).unwrap();
+    let next = Tx::new(&wallet, String::from("other"), String::from("5"), 3).unwrap();
+    peer.add_tx(&replaced).unwrap();
+    peer.add_tx(&next).unwrap();
+
+    let included = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    let following = Tx::new(&wallet, String::from("to"), String::from("10"), 3).unwrap();
+    blockchain.add_tx(&included).unwrap();
+    blockchain.add_tx(&following).unwrap();
+    let block = blockchain.create_block().unwrap();
+    peer.add_block(&block).unwrap();
+
+    let receipt = peer.receipt(replaced.hash_str()).unwrap().unwrap();
+    assert_eq!(receipt.status, TxStatus::Replaced);
+    assert_eq!(receipt.replaced_by, Some(included.hash_str()));
+    assert!(!receipt.success);
+    let receipt = peer.receipt(next.hash_str()).unwrap().unwrap();
+    assert_eq!(receipt.status, TxStatus::Replaced);
+    assert_eq!(receipt.replaced_by, Some(following.hash_str()));
+    assert!(peer.find_tx(replaced.hash_str()).unwrap().is_none());
+    assert!(peer.pending_tx_hashes().unwrap().is_empty());
+    assert_eq!(
+        peer.receipt(included.hash_str()).unwrap().unwrap().status,
+        TxStatus::Included
+    );
+}
+
+#[test]
+fn test_block_with_nonce_gap_rejected() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+
+    let included = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    let skipped = Tx::new(&wallet, String::from("to"), String::from("10"), 4).unwrap();
+    let latest = blockchain.find_latest().unwrap();
+    let txs = vec![included, skipped];
+    let block = Block::new(
+        &wallet,
+        latest.idx + 1,
+        latest.hash_str(),
+        txs,
+        &State::default(),
+    )
+    .unwrap();
+    assert!(blockchain.add_block(&block).is_err());
+    assert_eq!(blockchain.find_latest().unwrap().idx, 0);
+}
@@ -0,0 +1,112 @@
+use crate::net::reputation::{BAN_THRESHOLD, Offence, PROOF_REQUEST_COST, Reputation};
+use libp2p::PeerId;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_ban_below_threshold() {
+    let mut reputation = Reputation::new(Duration::from_secs(3600), 10);
+    let peer = PeerId::random();
+    let other = PeerId::random();
+    let mut banned = false;
+    while !banned {
+        assert!(reputation.score(&peer) > BAN_THRESHOLD);
+        banned = reputation.penalize(peer, Offence::InvalidGossip);
+    }
+    assert!(reputation.is_banned(&peer));
+    assert!(!reputation.is_banned(&other));
+    assert!(!reputation.penalize(peer, Offence::InvalidGossip));
+    assert!(reputation.expire_bans().is_empty());
+}
+
+#[test]
+fn test_ban_expires() {
+    let mut reputation = Reputation::new(Duration::ZERO, 10);
+    let peer = PeerId::random();
+    for _ in 0..4 {
+        reputation.penalize(peer, Offence::BadBlockResponse);
+    }
+    assert!(reputation.is_banned(&peer));
+    assert_eq!(reputation.expire_bans(), vec![peer]);
+    assert!(!reputation.is_banned(&peer));
+    assert_eq!(reputation.score(&peer), 0.0);
+}
+
+#[test]
+fn test_score_decay() {
+    let mut reputation = Reputation::new(Duration::from_secs(3600), 10);
+    let peer = PeerId::random();
+    reputation.penalize(peer, Offence::Timeout);
+    assert_eq!(reputation.score(&peer), -5.0);
+    reputation.decay();
+    reputation.decay();
+    assert_eq!(reputation.score(&peer), 0.0);
+}
+
+#[test]
+fn test_request_rate_limit() {
+    let now = Arc::new(Mutex::new(Instant::now()));
+    let clock = now.clone();
+    let mut reputation = Reputation::with_clock(
+        Duration::from_secs(3600),
+        3,
+        Box::new(move || *clock.lock().unwrap()),
+    );
+    let peer = PeerId::random();
+    for _ in 0..3 {
+        assert!(reputation.allow_request(peer, 1.0));
+    }
+    assert!(!reputation.allow_request(peer, 1.0));
+    assert!(reputation.allow_request(PeerId::random(), 1.0));
+    *now.lock().unwrap() += Duration::from_millis(400);
+    assert!(reputation.allow_request(peer, 1.0));
+}
+
+#[test]
+fn test_costly_requests() {
+    let now = Arc::new(Mutex::new(Instant::now()));
+    let clock = now.clone();
+    let mut reputation = Reputation::with_clock(
+        Duration::from_secs(3600),
+        20,
+        Box::new(move || *clock.lock().unwrap()),
+    );
+    let peer = PeerId::random();
+    assert!(reputation.allow_request(peer, PROOF_REQUEST_COST));
+    assert!(reputation.allow_request(peer, PROOF_REQUEST_COST));
+    assert!(!reputation.allow_request(peer, PROOF_REQUEST_COST));
+    *now.lock().unwrap() += Duration::from_millis(100);
+    assert!(reputation.allow_request(peer, 1.0));
+    assert!(!reputation.allow_request(peer, PROOF_REQUEST_COST));
+
+    let mut reputation = Reputation::new(Duration::from_secs(3600), 3);
+    assert!(reputation.allow_request(peer, PROOF_REQUEST_COST));
+}
+
+#[test]
+fn test_evict_neutral_disconnected_peers() {
+    let mut reputation = Reputation::new(Duration::from_secs(3600), 10);
+    let connected = PeerId::random();
+    let disconnected = PeerId::random();
+    let penalized = PeerId::random();
+    reputation.allow_request(connected, 1.0);
+    reputation.allow_request(disconnected, 1.0);
+    reputation.penalize(penalized, Offence::RequestFlood);
+    assert_eq!(reputation.evict(|peer| *peer == connected), 1);
+    assert_eq!(reputation.scores().len(), 2);
+    reputation.decay();
+    reputation.decay();
+    assert_eq!(reputation.evict(|peer| *peer == connected), 1);
+    assert_eq!(reputation.scores(), vec![(connected, 0.0)]);
+}
+
+#[test]
+fn test_ban_without_penalty() {
+    let mut reputation = Reputation::new(Duration::ZERO, 10);
+    let peer = PeerId::random();
+    assert!(reputation.ban(peer));
+    assert!(!reputation.ban(peer));
+    assert!(reputation.is_banned(&peer));
+    assert_eq!(reputation.score(&peer), 0.0);
+    assert_eq!(reputation.expire_bans(), vec![peer]);
+}
@@ -0,0 +1,162 @@
+use crate::api::rpc::{
+    INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR, Rpc,
+};
+use crate::api::server;
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::storage::db::Access;
+use crate::test::commons::{config, wallet_with_balance};
+use chain::proof::TxProof;
+use chain::tx::Tx;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+#[test]
+fn test_rpc_queries() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Arc::new(Blockchain::new(wallet.clone(), &config).unwrap());
+    let (sender, _receiver) = mpsc::channel(10);
+    let rpc = Rpc::new(Arc::clone(&blockchain), sender);
+
+    let head = rpc
+        .handle_json(r#"{"jsonrpc": "2.0", "id": 1, "method": "head"}"#)
+        .unwrap();
+    assert_eq!(head["id"], 1);
+    assert_eq!(head["result"]["idx"], 0);
+
+    let block = rpc
+        .handle_json(r#"{"jsonrpc": "2.0", "id": 2, "method": "block_by_idx", "params": [5]}"#)
+        .unwrap();
+    assert_eq!(block["result"], json!(null));
+
+    let request = json!([
+        { "jsonrpc": "2.0", "id": 3, "method": "balance", "params": [wallet.address()] },
+        { "jsonrpc": "2.0", "id": 4, "method": "stake", "params": [wallet.address()] },
+        { "jsonrpc": "2.0", "id": 5, "method": "nonce", "params": [wallet.address()] },
+        { "jsonrpc": "2.0", "id": 6, "method": "validators" },
+    ]);
+    let batch = rpc.handle_json(&request.to_string()).unwrap();
+    assert_eq!(batch[0]["result"], "500000");
+    assert_eq!(batch[1]["result"], "500000");
+    assert_eq!(batch[2]["result"], 1);
+    assert_eq!(batch[3]["result"][0]["address"], wallet.address());
+}
+
+#[test]
+fn test_rpc_send_raw_tx() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Arc::new(Blockchain::new(wallet.clone(), &config).unwrap());
+    let (sender, mut receiver) = mpsc::channel(10);
+    let rpc = Rpc::new(Arc::clone(&blockchain), sender);
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "send_raw_tx", "params": [tx] });
+    let response = rpc.handle_json(&request.to_string()).unwrap();
+    assert_eq!(response["result"], tx.hash_str());
+    assert_eq!(receiver.try_recv().unwrap(), tx);
+
+    let mempool = rpc
+        .handle_json(r#"{"jsonrpc": "2.0", "id": 2, "method": "mempool"}"#)
+        .unwrap();
+    assert_eq!(mempool["result"][0]["hash"], tx.hash_str());
+    let found = rpc.handle_json(
+        &json!({ "jsonrpc": "2.0", "id": 3, "method": "tx_by_hash", "params": [tx.hash_str()] })
+            .to_string(),
+    ).unwrap();
+    assert_eq!(found["result"]["nonce"], 2);
+    let proof_request =
+        json!({ "jsonrpc": "2.0", "id": 4, "method": "tx_proof", "params": [tx.hash_str()] });
+    assert_eq!(
+        rpc.handle_json(&proof_request.to_string()).unwrap()["result"],
+        json!(null)
+    );
+
+    let header = blockchain.create_block().unwrap();
+    let response = rpc.handle_json(&proof_request.to_string()).unwrap();
+    let proof: TxProof = serde_json::from_value(response["result"].clone()).unwrap();
+    assert_eq!(proof.tx.hash_str(), tx.hash_str());
+    assert!(proof.verify_against(&header));
+
+    let response = rpc.handle_json(&request.to_string()).unwrap();
+    assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    assert!(receiver.try_recv().is_err());
+}
+
+#[test]
+fn test_rpc_errors() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Arc::new(Blockchain::new(wallet, &config).unwrap());
+    let (sender, _receiver) = mpsc::channel(10);
+    let rpc = Rpc::new(blockchain, sender);
+
+    let response = rpc.handle_json("{").unwrap();
+    assert_eq!(response["error"]["code"], PARSE_ERROR);
+    let response = rpc
+        .handle_json(r#"{"jsonrpc": "2.0", "id": 1, "method": "unknown"}"#)
+        .unwrap();
+    assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    let response = rpc
+        .handle_json(r#"{"jsonrpc": "2.0", "id": 2, "method": "balance"}"#)
+        .unwrap();
+    assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    assert!(response.get("result").is_none());
+}
+
+#[test]
+fn test_rpc_notifications() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Arc::new(Blockchain::new(wallet.clone(), &config).unwrap());
+    let (sender, mut receiver) = mpsc::channel(10);
+    let rpc = Rpc::new(Arc::clone(&blockchain), sender);
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    let notification = json!({ "jsonrpc": "2.0", "method": "send_raw_tx", "params": [tx] });
+    assert_eq!(rpc.handle_json(&notification.to_string()), None);
+    assert_eq!(receiver.try_recv().unwrap(), tx);
+
+    let request = json!([
+        { "jsonrpc": "2.0", "method": "head" },
+        { "jsonrpc": "2.0", "id": 1, "method": "head" },
+        { "jsonrpc": "2.0", "id": null, "method": "head" },
+        { "jsonrpc": "2.0" },
+    ]);
+    let batch = rpc.handle_json(&request.to_string()).unwrap();
+    assert_eq!(batch.as_array().unwrap().len(), 3);
+    assert_eq!(batch[0]["id"], 1);
+    assert_eq!(batch[1]["id"], json!(null));
+    assert_eq!(batch[2]["error"]["code"], INVALID_REQUEST);
+
+    let request = json!([{ "jsonrpc": "2.0", "method": "head" }]);
+    assert_eq!(rpc.handle_json(&request.to_string()), None);
+}
+
+#[test]
+fn test_rpc_internal_error() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    Blockchain::new(wallet.clone(), &config).unwrap();
+    let read_only = Arc::new(Blockchain::open(&config, &Access::ReadOnly).unwrap());
+    let (sender, _receiver) = mpsc::channel(10);
+    let rpc = Rpc::new(read_only, sender);
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "send_raw_tx", "params": [tx] });
+    let response = rpc.handle_json(&request.to_string()).unwrap();
+    assert_eq!(response["error"]["code"], INTERNAL_ERROR);
+}
+
+#[tokio::test]
+async fn test_bind_error_is_returned() {
+    let listener = server::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    assert!(server::bind(&address).await.is_err());
+}
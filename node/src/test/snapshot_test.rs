@@ -0,0 +1,100 @@
+use crate::blockchain::blockchain::Blockchain;
+use crate::test::commons::{config, peer_config, wallet_with_balance};
+use bigdecimal::BigDecimal;
+use chain::block::Block;
+use chain::headers::HeaderChain;
+use chain::state::State;
+use chain::tx::Tx;
+use std::str::FromStr;
+use wallet::wallet::Wallet;
+
+#[test]
+fn test_snapshot_create_apply() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let tx = Tx::new(&wallet, String::from("to"), String::from("100.5"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    let block = blockchain.create_block().unwrap();
+
+    let manifest = blockchain.create_snapshot(block.idx).unwrap();
+    assert!(manifest.valid());
+    assert_eq!(manifest.tx_count, blockchain.tx_count().unwrap());
+    assert_eq!(blockchain.find_snapshot().unwrap(), Some(manifest.clone()));
+    let mut chunks = Vec::new();
+    for idx in 0..manifest.chunks.len() as u64 {
+        let chunk = blockchain
+            .find_snapshot_chunk(manifest.height, idx)
+            .unwrap()
+            .unwrap();
+        assert!(manifest.verify_chunk(&chunk));
+        chunks.push(chunk);
+    }
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("1"), 3).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    let next_block = blockchain.create_block().unwrap();
+
+    let sync_dir = tempfile::tempdir().unwrap();
+    let synced = Blockchain::new(Wallet::new(), &peer_config(&config, sync_dir.path())).unwrap();
+    let mut headers = HeaderChain::new(synced.find_latest().unwrap(), synced.stakes().unwrap());
+    assert!(synced.apply_snapshot(&manifest, &chunks, &headers).is_err());
+    headers
+        .append(&manifest.block, blockchain.validators(block.idx).unwrap())
+        .unwrap();
+    let mut tampered = chunks.clone();
+    tampered[0].accounts[0].balance = String::from("1");
+    assert!(
+        synced
+            .apply_snapshot(&manifest, &tampered, &headers)
+            .is_err()
+    );
+
+    synced.apply_snapshot(&manifest, &chunks, &headers).unwrap();
+    assert_eq!(synced.find_latest().unwrap().hash_str(), block.hash_str());
+    assert_eq!(synced.nonce(wallet.address()).unwrap(), 2);
+    assert_eq!(
+        synced.balance(wallet.address()).unwrap(),
+        BigDecimal::from_str("499899.5").unwrap()
+    );
+    assert_eq!(
+        synced
+            .wallet_stake(wallet.address())
+            .unwrap()
+            .unwrap()
+            .stake(),
+        blockchain
+            .wallet_stake(wallet.address())
+            .unwrap()
+            .unwrap()
+            .stake()
+    );
+
+    synced.add_block(&next_block).unwrap();
+    assert_eq!(
+        synced.balance(wallet.address()).unwrap(),
+        blockchain.balance(wallet.address()).unwrap()
+    );
+    assert_eq!(synced.nonce(wallet.address()).unwrap(), 3);
+    assert_eq!(synced.tx_count().unwrap(), blockchain.tx_count().unwrap());
+    assert_eq!(synced.block_tx_count(next_block.idx).unwrap(), 1);
+}
+
+#[test]
+fn test_snapshot_from_unselected_validator_rejected() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Blockchain::new(wallet.clone(), &config).unwrap();
+    let genesis = blockchain.find_latest().unwrap();
+
+    let forger = Wallet::new();
+    let mut state = State::default();
+    let mint = Tx::new(&forger, forger.address(), String::from("1"), 1).unwrap();
+    state.apply(&mint);
+    let forged = Block::new(&forger, 1, genesis.hash_str(), vec![mint], &state).unwrap();
+    let mut headers = HeaderChain::new(genesis, blockchain.stakes().unwrap());
+    assert!(headers.append(&forged, Some(state.stakes())).is_err());
+    assert_eq!(headers.height(), 0);
+}
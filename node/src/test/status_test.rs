@@ -0,0 +1,47 @@
+use crate::net::status::{PeerStatus, StatusExt};
+use chain::wire::{STATUS_VERSION, Status};
+
+fn status(head_height: u64) -> Status {
+    Status {
+        version: String::from(STATUS_VERSION),
+        chain_id: String::from("xhcg"),
+        genesis_hash: String::from("genesis"),
+        head_height,
+        head_hash: format!("hash{}", head_height),
+        finalized_height: head_height.saturating_sub(10),
+        earliest_body: 0,
+    }
+}
+
+#[test]
+fn test_compare_chain() {
+    let local = status(5);
+    let mut remote = status(5);
+    remote.chain_id = String::from("other");
+    assert_eq!(local.compare(&remote), PeerStatus::Incompatible);
+    let mut remote = status(5);
+    remote.genesis_hash = String::from("other");
+    assert_eq!(local.compare(&remote), PeerStatus::Incompatible);
+    let mut remote = status(5);
+    remote.version = String::from("9.9.9");
+    assert_eq!(local.compare(&remote), PeerStatus::Incompatible);
+    remote.version = String::from("0.1.0");
+    assert_eq!(local.compare(&remote), PeerStatus::Incompatible);
+    remote.version = String::from("0.0");
+    assert_eq!(local.compare(&remote), PeerStatus::Incompatible);
+    remote.version = String::from("0.0.9");
+    assert_eq!(local.compare(&remote), PeerStatus::Behind);
+}
+
+#[test]
+fn test_compare_head() {
+    let local = status(5);
+    assert_eq!(local.compare(&status(3)), PeerStatus::Behind);
+    assert_eq!(local.compare(&status(5)), PeerStatus::Behind);
+    assert_eq!(local.compare(&status(8)), PeerStatus::Ahead);
+    let mut remote = status(200);
+    remote.earliest_body = 101;
+    assert_eq!(local.compare(&remote), PeerStatus::Pruned);
+    remote.earliest_body = 6;
+    assert_eq!(local.compare(&remote), PeerStatus::Ahead);
+}
@@ -0,0 +1,144 @@
+use crate::net::sync::{MAX_RANGE, Range, SyncManager};
+use chain::block::Block;
+use chain::state::State;
+use libp2p::PeerId;
+use wallet::wallet::Wallet;
+
+fn blocks(from: u64, count: u64) -> Vec<Block> {
+    let wallet = Wallet::new();
+    (from..from + count)
+        .map(|idx| Block::new(&wallet, idx, String::new(), vec![], &State::default()).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_ranges_spread_across_peers() {
+    let mut sync = SyncManager::new();
+    let first = PeerId::random();
+    let second = PeerId::random();
+    sync.update_peer(first, 300);
+    sync.update_peer(second, 300);
+    assert!(sync.is_syncing(0));
+
+    let requests = sync.next_requests(0);
+    assert_eq!(requests.len(), 4);
+    assert_eq!(
+        requests[0].1,
+        Range {
+            from: 1,
+            count: MAX_RANGE
+        }
+    );
+    assert_eq!(requests[1].1.from, 1 + MAX_RANGE);
+    for peer in [first, second] {
+        assert_eq!(requests.iter().filter(|(p, _)| *p == peer).count(), 2);
+    }
+    assert!(sync.next_requests(0).is_empty());
+
+    sync.on_response(1 + MAX_RANGE, blocks(1 + MAX_RANGE, MAX_RANGE));
+    assert!(sync.ready(0).is_empty());
+    sync.on_response(1, blocks(1, MAX_RANGE));
+    let ready = sync.ready(0);
+    assert_eq!(ready.len(), 2 * MAX_RANGE as usize);
+    assert!(
+        ready
+            .iter()
+            .enumerate()
+            .all(|(i, (_, b))| b.idx == i as u64 + 1)
+    );
+}
+
+#[test]
+fn test_failed_range_retried_on_other_peer() {
+    let mut sync = SyncManager::new();
+    let first = PeerId::random();
+    sync.update_peer(first, 10);
+    let requests = sync.next_requests(0);
+    assert_eq!(requests, vec![(first, Range { from: 1, count: 10 })]);
+
+    let second = PeerId::random();
+    sync.update_peer(second, 10);
+    sync.on_response(1, blocks(1, 4));
+    let requests = sync.next_requests(0);
+    assert_eq!(requests, vec![(second, Range { from: 5, count: 6 })]);
+
+    sync.on_failure(5);
+    let requests = sync.next_requests(4);
+    assert_eq!(requests, vec![(first, Range { from: 5, count: 6 })]);
+
+    sync.remove_peer(&first);
+    let requests = sync.next_requests(4);
+    assert_eq!(requests, vec![(second, Range { from: 5, count: 6 })]);
+}
+
+#[test]
+fn test_reject_refetches_from_block() {
+    let mut sync = SyncManager::new();
+    let first = PeerId::random();
+    let second = PeerId::random();
+    sync.update_peer(first, 5);
+    sync.next_requests(0);
+    sync.update_peer(second, 5);
+    sync.on_response(1, blocks(1, 5));
+    let ready = sync.ready(0);
+    assert_eq!(ready.len(), 5);
+
+    sync.reject(3, first, ready.into_iter().skip(3).collect());
+    let requests = sync.next_requests(2);
+    assert_eq!(requests, vec![(second, Range { from: 3, count: 3 })]);
+    sync.on_response(3, blocks(3, 3));
+    assert_eq!(sync.ready(2).len(), 3);
+    assert!(!sync.is_syncing(5));
+}
+
+#[test]
+fn test_reject_keeps_other_ranges() {
+    let mut sync = SyncManager::new();
+    let first = PeerId::random();
+    let second = PeerId::random();
+    sync.update_peer(first, 2 * MAX_RANGE + 10);
+    sync.update_peer(second, 2 * MAX_RANGE + 10);
+    let requests = sync.next_requests(0);
+    assert_eq!(requests.len(), 3);
+    let (owner, range) = requests[0];
+    let other = if owner == first { second } else { first };
+    sync.on_response(range.from, blocks(range.from, range.count));
+    let ready = sync.ready(0);
+    assert_eq!(ready.len(), MAX_RANGE as usize);
+
+    sync.reject(10, owner, ready.into_iter().skip(10).collect());
+    let retried = sync.next_requests(9);
+    assert_eq!(
+        retried,
+        vec![(
+            other,
+            Range {
+                from: 10,
+                count: MAX_RANGE - 9
+            }
+        )]
+    );
+    assert!(sync.next_requests(9).is_empty());
+    sync.on_response(1 + MAX_RANGE, blocks(1 + MAX_RANGE, MAX_RANGE));
+    sync.on_response(10, blocks(10, MAX_RANGE - 9));
+    assert_eq!(sync.ready(9).len(), 2 * MAX_RANGE as usize - 9);
+}
+
+#[test]
+fn test_fork_stops_syncing_from_peer() {
+    let mut sync = SyncManager::new();
+    let first = PeerId::random();
+    let second = PeerId::random();
+    sync.update_peer(first, 5);
+    sync.next_requests(0);
+    sync.on_response(1, blocks(1, 5));
+    let ready = sync.ready(0);
+
+    sync.fork(1, first, ready.into_iter().skip(1).collect());
+    assert!(!sync.is_syncing(0));
+    sync.update_peer(second, 5);
+    assert_eq!(
+        sync.next_requests(0),
+        vec![(second, Range { from: 1, count: 5 })]
+    );
+}
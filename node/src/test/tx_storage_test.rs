@@ -1,9 +1,9 @@
-use std::fs;
-use std::sync::Arc;
 use crate::blockchain::storage::db;
 use crate::blockchain::storage::tx_storage::TxStorage;
-use chain::tx::Tx;
 use crate::test::commons::{config, wallet, wallet_with_balance};
+use chain::tx::Tx;
+use std::fs;
+use std::sync::Arc;
 
 #[test]
 fn test_tx_storage_save_find_by_hash() -> Result<(), std::io::Error> {
@@ -67,3 +67,36 @@ fn update_pending() {
     assert_eq!(txs.len(), 0);
     fs::remove_dir_all(config.storage_path()).unwrap();
 }
+
+#[test]
+fn wallet_txs_page() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let db = db::open(&config).unwrap();
+    let tx_storage = TxStorage::new(Arc::clone(&db));
+    let from = wallet(&config);
+    let other = wallet(&config);
+    for nonce in 1..4 {
+        let tx = Tx::new(&from, String::from("to"), String::from("1"), nonce).unwrap();
+        tx_storage.save(&tx).unwrap();
+        let tx = Tx::new(&other, String::from("to"), String::from("1"), nonce).unwrap();
+        tx_storage.save(&tx).unwrap();
+    }
+
+    let page = tx_storage
+        .find_wallet_txs_page(&from.address(), 1, 10)
+        .unwrap();
+    assert_eq!(page.len(), 2);
+    assert!(page.iter().all(|tx| tx.from() == from.address()));
+    let page = tx_storage
+        .find_wallet_txs_page(&from.address(), 3, 10)
+        .unwrap();
+    assert!(page.is_empty());
+
+    db.put(format!("wallet_tx.{}.bad", from.address()), b"")
+        .unwrap();
+    let error = tx_storage
+        .find_wallet_txs_page(&from.address(), 0, 10)
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
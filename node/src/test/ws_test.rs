@@ -0,0 +1,149 @@
+use crate::api::ws::{self, MAX_REPLAY_BLOCKS, Subscription, Topic};
+use crate::blockchain::blockchain::Blockchain;
+use crate::blockchain::events::ChainEvent;
+use crate::test::commons::{config, wallet_with_balance, with_options};
+use chain::tx::Tx;
+use futures::StreamExt;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn subscribe(address: &str, query: &str) -> Socket {
+    let url = format!("ws://{}/ws?{}", address, query);
+    tokio_tungstenite::connect_async(url).await.unwrap().0
+}
+
+async fn next_event(socket: &mut Socket) -> ChainEvent {
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_subscriptions_resume_and_stream() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = with_options(&config(temp_dir.path()), json!({ "finality_depth": 1 }));
+    let wallet = wallet_with_balance(&config).unwrap();
+    let blockchain = Arc::new(Blockchain::new(wallet.clone(), &config).unwrap());
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    blockchain.create_block().unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    tokio::spawn(axum::serve(listener, ws::router(Arc::clone(&blockchain))).into_future());
+
+    let mut heads = subscribe(&address, "topic=new_heads&from=0").await;
+    for idx in 0..2 {
+        match next_event(&mut heads).await {
+            ChainEvent::NewHead { block } => assert_eq!(block.idx, idx),
+            event => panic!("Unexpected event {:?}", event),
+        }
+    }
+    let mut finalized = subscribe(&address, "topic=finalized&from=0").await;
+    match next_event(&mut finalized).await {
+        ChainEvent::Finalized { block } => assert_eq!(block.idx, 0),
+        event => panic!("Unexpected event {:?}", event),
+    }
+    let mut txs = subscribe(&address, "topic=address_txs&address=to&from=1").await;
+    match next_event(&mut txs).await {
+        ChainEvent::IncludedTx { tx: included } => {
+            assert_eq!(included.hash_str(), tx.hash_str());
+            assert_eq!(included.block, Some(1));
+        }
+        event => panic!("Unexpected event {:?}", event),
+    }
+
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 3).unwrap();
+    blockchain.add_tx(&tx).unwrap();
+    blockchain.create_block().unwrap();
+
+    match next_event(&mut heads).await {
+        ChainEvent::NewHead { block } => assert_eq!(block.idx, 2),
+        event => panic!("Unexpected event {:?}", event),
+    }
+    match next_event(&mut finalized).await {
+        ChainEvent::Finalized { block } => assert_eq!(block.idx, 1),
+        event => panic!("Unexpected event {:?}", event),
+    }
+    assert_eq!(
+        next_event(&mut txs).await,
+        ChainEvent::PendingTx { tx: tx.clone() }
+    );
+    match next_event(&mut txs).await {
+        ChainEvent::IncludedTx { tx } => assert_eq!(tx.block, Some(2)),
+        event => panic!("Unexpected event {:?}", event),
+    }
+}
+
+#[test]
+fn test_subscription_filters() {
+    let wallet = wallet::wallet::Wallet::new();
+    let tx = Tx::new(&wallet, String::from("to"), String::from("10"), 2).unwrap();
+    let pending = ChainEvent::PendingTx { tx };
+    let subscription = |topic, address: Option<&str>| Subscription {
+        topic,
+        address: address.map(String::from),
+        from: None,
+    };
+    assert!(subscription(Topic::PendingTxs, None).matches(&pending));
+    assert!(subscription(Topic::AddressTxs, Some("to")).matches(&pending));
+    assert!(subscription(Topic::AddressTxs, Some(&wallet.address())).matches(&pending));
+    assert!(!subscription(Topic::AddressTxs, Some("other")).matches(&pending));
+    assert!(!subscription(Topic::NewHeads, None).matches(&pending));
+}
+
+#[test]
+fn test_replay_window() {
+    let subscription = |from| Subscription {
+        topic: Topic::NewHeads,
+        address: None,
+        from,
+    };
+    assert!(!subscription(None).exceeds_replay_window(MAX_REPLAY_BLOCKS * 2));
+    assert!(!subscription(Some(1)).exceeds_replay_window(MAX_REPLAY_BLOCKS));
+    assert!(subscription(Some(0)).exceeds_replay_window(MAX_REPLAY_BLOCKS));
+}
+
+#[tokio::test]
+async fn test_replay_of_pruned_blocks_closes_stream() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = config(temp_dir.path());
+    let wallet = wallet_with_balance(&config).unwrap();
+    let options = json!({ "storage_mode": "full", "keep_blocks": 1, "finality_depth": 1 });
+    let config = with_options(&config, options);
+    let blockchain = Arc::new(Blockchain::new(wallet.clone(), &config).unwrap());
+    for nonce in 2..5 {
+        let tx = Tx::new(&wallet, String::from("to"), String::from("10"), nonce).unwrap();
+        blockchain.add_tx(&tx).unwrap();
+        blockchain.create_block().unwrap();
+    }
+    blockchain.prune().unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    tokio::spawn(axum::serve(listener, ws::router(Arc::clone(&blockchain))).into_future());
+
+    let mut heads = subscribe(&address, "topic=new_heads&from=1").await;
+    let message = tokio::time::timeout(Duration::from_secs(5), heads.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    match message {
+        Message::Close(Some(frame)) => assert_eq!(frame.reason.as_str(), "Block 1 is pruned"),
+        message => panic!("Unexpected message {:?}", message),
+    }
+}